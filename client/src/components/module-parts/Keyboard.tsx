@@ -4,17 +4,21 @@ const WHITE_KEYS = ['C', 'D', 'E', 'F', 'G', 'A', 'B']
 const BLACK_KEYS = ['C#', 'D#', null, 'F#', 'G#', 'A#']
 
 type Props = {
-  note: string
+  // Either a single selected note, or a set of them.
+  note: string | string[]
   onChange: (note: string) => void
 }
 
 const Keyboard = (props: Props) => {
+  const isOn = (key: string) =>
+    Array.isArray(props.note) ? props.note.includes(key) : key === props.note
+
   return (
     <div class={styles.keyboard}>
       {WHITE_KEYS.map((key, i) => (
         <button
           onClick={() => props.onChange(key)}
-          class={() => [styles.whiteKey, { [styles.on]: key === props.note }]}
+          class={() => [styles.whiteKey, { [styles.on]: isOn(key) }]}
           style={{ left: i * 22 + 'px' }}
         ></button>
       ))}
@@ -23,10 +27,7 @@ const Keyboard = (props: Props) => {
           key && (
             <button
               onClick={() => props.onChange(key)}
-              class={() => [
-                styles.blackKey,
-                { [styles.on]: key === props.note },
-              ]}
+              class={() => [styles.blackKey, { [styles.on]: isOn(key) }]}
               style={{ left: i * 22 + 10 + 'px' }}
             ></button>
           )
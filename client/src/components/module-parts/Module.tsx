@@ -1,6 +1,7 @@
 import { FC, useRef } from 'kaiku'
 import state, {
   cloneModule,
+  clearModuleFault,
   deleteModule,
   getModulePosition,
   openContextMenu,
@@ -28,6 +29,15 @@ const Module: FC<Props> = ({ id, type, name, children }) => {
       title: `Module - ${name ?? type}`,
       width: 180,
       items: [
+        ...(state.moduleFaults[id]
+          ? [
+              {
+                type: 'item' as const,
+                name: 'Clear fault',
+                action: () => clearModuleFault(id),
+              },
+            ]
+          : []),
         {
           type: 'item',
           name: 'Reset knobs',
@@ -64,6 +74,7 @@ const Module: FC<Props> = ({ id, type, name, children }) => {
   })
 
   const config = moduleConfig[type]
+  const fault = state.moduleFaults[id]
 
   return (
     <div
@@ -103,6 +114,19 @@ const Module: FC<Props> = ({ id, type, name, children }) => {
         {...testAttributes({ id: 'module-header' })}
       >
         {name ?? type}
+        {fault && (
+          <span
+            class={styles.fault}
+            title={
+              fault.reason === 'Panic'
+                ? 'The module crashed and was silenced, click to clear'
+                : 'The module output invalid values and was silenced, click to clear'
+            }
+            onClick={() => clearModuleFault(id)}
+          >
+            FAULT
+          </span>
+        )}
       </div>
       <div class={styles.moduleBody}>{children}</div>
     </div>
@@ -0,0 +1,169 @@
+import { Component } from 'kaiku'
+import * as engine from '../../engine'
+import Socket from '../module-parts/Socket'
+import Module from '../module-parts/Module'
+import Knob from '../module-parts/Knob'
+import ModuleControls from '../module-parts/ModuleControls'
+import { ModuleInputs, ModuleOutputs } from '../module-parts/ModuleSockets'
+import { Arpeggiator } from '@modulate/worklets/src/modules'
+
+type Props = {
+  id: string
+}
+
+const MODE_OPTIONS = [
+  { label: 'UP', value: 0 },
+  { label: 'DOWN', value: 1 },
+  { label: 'U/D', value: 2 },
+  { label: 'RAND', value: 3 },
+  { label: 'PLAY', value: 4 },
+]
+
+const LATCH_OPTIONS = [
+  { label: 'OFF', value: 0 },
+  { label: 'ON', value: 1 },
+]
+
+class ArpeggiatorNode extends Component<Props> {
+  constructor(props: Props) {
+    super(props)
+
+    navigator.requestMIDIAccess().then(
+      (midiAccess) => {
+        midiAccess.inputs.forEach((entry) => {
+          entry.addEventListener('midimessage', (msg) => {
+            const midiEvent = msg as WebMidi.MIDIMessageEvent
+            let data = 0
+            for (let i = 0; i < midiEvent.data.length; i++) {
+              data |= midiEvent.data[i]! << (i * 8)
+            }
+
+            engine.sendMessageToModule<Arpeggiator>(props.id, {
+              type: 'MidiMessage',
+              message: data,
+            })
+          })
+        })
+      },
+      () => {
+        // TODO: Handle gracefully
+      }
+    )
+  }
+
+  render({ id }: Props) {
+    return (
+      <Module id={id} type="Arpeggiator">
+        <ModuleControls>
+          <Knob<Arpeggiator, 'mode'>
+            moduleId={id}
+            param={0}
+            label="MODE"
+            type="option"
+            options={MODE_OPTIONS}
+            initial={0}
+          />
+          <Knob<Arpeggiator, 'octaves'>
+            moduleId={id}
+            param={1}
+            label="OCT"
+            type="stepped"
+            step={1}
+            min={1}
+            max={4}
+            initial={1}
+          />
+          <Knob<Arpeggiator, 'gateLength'>
+            moduleId={id}
+            param={2}
+            label="GATE"
+            type="percentage"
+            initial={0.5}
+          />
+          <Knob<Arpeggiator, 'latch'>
+            moduleId={id}
+            param={3}
+            label="LATCH"
+            type="option"
+            options={LATCH_OPTIONS}
+            initial={0}
+          />
+        </ModuleControls>
+
+        <ModuleInputs>
+          <Socket<Arpeggiator, 'input', 'clock'>
+            moduleId={id}
+            type="input"
+            index={0}
+            label="CLK"
+          />
+          <Socket<Arpeggiator, 'input', 'cv0'>
+            moduleId={id}
+            type="input"
+            index={1}
+            label="CV1"
+          />
+          <Socket<Arpeggiator, 'input', 'gate0'>
+            moduleId={id}
+            type="input"
+            index={2}
+            label="GT1"
+          />
+          <Socket<Arpeggiator, 'input', 'cv1'>
+            moduleId={id}
+            type="input"
+            index={3}
+            label="CV2"
+          />
+          <Socket<Arpeggiator, 'input', 'gate1'>
+            moduleId={id}
+            type="input"
+            index={4}
+            label="GT2"
+          />
+          <Socket<Arpeggiator, 'input', 'cv2'>
+            moduleId={id}
+            type="input"
+            index={5}
+            label="CV3"
+          />
+          <Socket<Arpeggiator, 'input', 'gate2'>
+            moduleId={id}
+            type="input"
+            index={6}
+            label="GT3"
+          />
+          <Socket<Arpeggiator, 'input', 'cv3'>
+            moduleId={id}
+            type="input"
+            index={7}
+            label="CV4"
+          />
+          <Socket<Arpeggiator, 'input', 'gate3'>
+            moduleId={id}
+            type="input"
+            index={8}
+            label="GT4"
+          />
+        </ModuleInputs>
+
+        <ModuleOutputs>
+          <Socket<Arpeggiator, 'output', 'cv'>
+            moduleId={id}
+            type="output"
+            index={0}
+            label="CV"
+          />
+          <Socket<Arpeggiator, 'output', 'gate'>
+            moduleId={id}
+            type="output"
+            index={1}
+            label="GATE"
+          />
+        </ModuleOutputs>
+      </Module>
+    )
+  }
+}
+
+export default ArpeggiatorNode
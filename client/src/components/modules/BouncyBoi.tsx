@@ -5,7 +5,7 @@ import Module from '../module-parts/Module'
 import Knob from '../module-parts/Knob'
 import { ModuleOutputs } from '../module-parts/ModuleSockets'
 import * as styles from './BouncyBoi.css'
-import { BouncyBoi, EventTypes } from '@modulate/worklets/src/modules'
+import { BouncyBoi } from '@modulate/worklets/src/modules'
 import moduleConfig from '../../module-config'
 import { darkenColor } from '../../colors'
 
@@ -18,11 +18,16 @@ const BALL_FILL_COLORS = BALL_BORDER_COLORS.map((color) =>
   darkenColor(color, 0.7)
 )
 
+// The position and velocity of each ball, followed by the phase of the walls.
+const NUM_BALLS = 3
+const BALL_STATE_LENGTH = 4
+const UI_STATE_LENGTH = NUM_BALLS * BALL_STATE_LENGTH + 1
+
 const BouncyBoiNode = ({ id }: Props) => {
   const canvasRef = useRef<HTMLCanvasElement>()
 
   useEffect(() => {
-    const renderCanvas = (state: EventTypes<BouncyBoi, 'BouncyBoiUpdate'>) => {
+    const renderCanvas = (state: Float32Array) => {
       const canvas = canvasRef.current
       if (!canvas) return
 
@@ -33,33 +38,30 @@ const BouncyBoiNode = ({ id }: Props) => {
 
       context.lineWidth = 2
 
-      for (let i = 0; i < state.balls.length; i++) {
-        const ball = state.balls[i]!
+      for (let i = 0; i < NUM_BALLS; i++) {
+        const x = state[i * BALL_STATE_LENGTH]!
+        const y = state[i * BALL_STATE_LENGTH + 1]!
         context.strokeStyle = BALL_BORDER_COLORS[i]!
         context.fillStyle = BALL_FILL_COLORS[i]!
 
         context.beginPath()
-        context.arc(
-          ball.pos.x * 0.5 + 75,
-          ball.pos.y * 0.5 + 75,
-          5,
-          0,
-          Math.PI * 2
-        )
+        context.arc(x * 0.5 + 75, y * 0.5 + 75, 5, 0, Math.PI * 2)
         context.fill()
         context.stroke()
       }
       context.strokeStyle = moduleConfig.BouncyBoi.colors.primary
 
+      const phase = state[NUM_BALLS * BALL_STATE_LENGTH]!
+
       for (let i = 0; i < 5; i++) {
         const from = {
-          x: Math.sin((i * Math.PI * 2) / 5 + state.phase) * 50 + 75,
-          y: Math.cos((i * Math.PI * 2) / 5 + state.phase) * 50 + 75,
+          x: Math.sin((i * Math.PI * 2) / 5 + phase) * 50 + 75,
+          y: Math.cos((i * Math.PI * 2) / 5 + phase) * 50 + 75,
         }
 
         const to = {
-          x: Math.sin(((i + 1) * Math.PI * 2) / 5 + state.phase) * 50 + 75,
-          y: Math.cos(((i + 1) * Math.PI * 2) / 5 + state.phase) * 50 + 75,
+          x: Math.sin(((i + 1) * Math.PI * 2) / 5 + phase) * 50 + 75,
+          y: Math.cos(((i + 1) * Math.PI * 2) / 5 + phase) * 50 + 75,
         }
         context.beginPath()
         context.moveTo(from.x, from.y)
@@ -68,19 +70,19 @@ const BouncyBoiNode = ({ id }: Props) => {
       }
     }
 
-    engine.onModuleEvent<BouncyBoi>(id, (message) => {
-      switch (message.type) {
-        case 'BouncyBoiUpdate': {
-          renderCanvas(message)
-          break
-        }
+    let readState: (() => Float32Array | null) | null = null
+    engine.getModulePointers(id).then((pointers) => {
+      readState = engine.createUiStateReader(pointers[0]!, UI_STATE_LENGTH)
+    })
 
-        default:
-          throw new Error(
-            `bouncyboi: unexpected message ${JSON.stringify(message)}`
-          )
+    const interval = setInterval(() => {
+      const state = readState?.()
+      if (state) {
+        renderCanvas(state)
       }
-    })
+    }, 16)
+
+    return () => clearInterval(interval)
   })
 
   return (
@@ -0,0 +1,93 @@
+import Socket from '../module-parts/Socket'
+import Module from '../module-parts/Module'
+import Knob from '../module-parts/Knob'
+import ModuleControls from '../module-parts/ModuleControls'
+import { ModuleInputs, ModuleOutputs } from '../module-parts/ModuleSockets'
+import { Chaos } from '@modulate/worklets/src/modules'
+
+type Props = {
+  id: string
+}
+
+const ChaosNode = ({ id }: Props) => {
+  return (
+    <Module id={id} type="Chaos">
+      <ModuleControls>
+        <Knob<Chaos, 'rate'>
+          moduleId={id}
+          param={0}
+          label="RATE"
+          type="exponential"
+          unit="Hz"
+          exponent={3}
+          min={0.01}
+          max={50}
+          initial={1}
+        />
+        <Knob<Chaos, 'chaos'>
+          moduleId={id}
+          param={1}
+          label="CHAOS"
+          type="percentage"
+          initial={0.5}
+        />
+      </ModuleControls>
+
+      <ModuleInputs>
+        <Socket<Chaos, 'parameter', 'rate'>
+          moduleId={id}
+          type="parameter"
+          index={0}
+          label="RATE"
+        />
+        <Socket<Chaos, 'parameter', 'chaos'>
+          moduleId={id}
+          type="parameter"
+          index={1}
+          label="CHAOS"
+        />
+      </ModuleInputs>
+
+      <ModuleOutputs>
+        <Socket<Chaos, 'output', 'smooth'>
+          moduleId={id}
+          type="output"
+          index={0}
+          label="SMTH"
+        />
+        <Socket<Chaos, 'output', 'stepped'>
+          moduleId={id}
+          type="output"
+          index={1}
+          label="STEP"
+        />
+        <Socket<Chaos, 'output', 'x'>
+          moduleId={id}
+          type="output"
+          index={2}
+          label="X"
+        />
+        <Socket<Chaos, 'output', 'y'>
+          moduleId={id}
+          type="output"
+          index={3}
+          label="Y"
+        />
+        <Socket<Chaos, 'output', 'z'>
+          moduleId={id}
+          type="output"
+          index={4}
+          label="Z"
+        />
+        <Socket<Chaos, 'output', 'logistic'>
+          moduleId={id}
+          type="output"
+          index={5}
+          label="LOG"
+        />
+      </ModuleOutputs>
+    </Module>
+  )
+}
+
+export default ChaosNode
@@ -0,0 +1,255 @@
+import Socket from '../module-parts/Socket'
+import Module from '../module-parts/Module'
+import Knob from '../module-parts/Knob'
+import { ModuleInputs, ModuleOutputs } from '../module-parts/ModuleSockets'
+import { Euclidean } from '@modulate/worklets/src/modules'
+import * as styles from './Euclidean.css'
+
+type Props = {
+  id: string
+}
+
+const EuclideanNode = ({ id }: Props) => {
+  return (
+    <Module id={id} type="Euclidean">
+      <div class={styles.euclidean}>
+        <Knob<Euclidean, 'steps0'>
+          moduleId={id}
+          param={0}
+          label="STEPS"
+          type="stepped"
+          step={1}
+          min={1}
+          max={32}
+          initial={16}
+        />
+        <Knob<Euclidean, 'pulses0'>
+          moduleId={id}
+          param={1}
+          label="HITS"
+          type="stepped"
+          step={1}
+          min={0}
+          max={32}
+          initial={4}
+        />
+        <Knob<Euclidean, 'rotation0'>
+          moduleId={id}
+          param={2}
+          label="ROT"
+          type="stepped"
+          step={1}
+          min={0}
+          max={31}
+          initial={0}
+        />
+        <Knob<Euclidean, 'steps1'>
+          moduleId={id}
+          param={3}
+          label="STEPS"
+          type="stepped"
+          step={1}
+          min={1}
+          max={32}
+          initial={16}
+        />
+        <Knob<Euclidean, 'pulses1'>
+          moduleId={id}
+          param={4}
+          label="HITS"
+          type="stepped"
+          step={1}
+          min={0}
+          max={32}
+          initial={3}
+        />
+        <Knob<Euclidean, 'rotation1'>
+          moduleId={id}
+          param={5}
+          label="ROT"
+          type="stepped"
+          step={1}
+          min={0}
+          max={31}
+          initial={0}
+        />
+        <Knob<Euclidean, 'steps2'>
+          moduleId={id}
+          param={6}
+          label="STEPS"
+          type="stepped"
+          step={1}
+          min={1}
+          max={32}
+          initial={8}
+        />
+        <Knob<Euclidean, 'pulses2'>
+          moduleId={id}
+          param={7}
+          label="HITS"
+          type="stepped"
+          step={1}
+          min={0}
+          max={32}
+          initial={5}
+        />
+        <Knob<Euclidean, 'rotation2'>
+          moduleId={id}
+          param={8}
+          label="ROT"
+          type="stepped"
+          step={1}
+          min={0}
+          max={31}
+          initial={0}
+        />
+        <Knob<Euclidean, 'steps3'>
+          moduleId={id}
+          param={9}
+          label="STEPS"
+          type="stepped"
+          step={1}
+          min={1}
+          max={32}
+          initial={16}
+        />
+        <Knob<Euclidean, 'pulses3'>
+          moduleId={id}
+          param={10}
+          label="HITS"
+          type="stepped"
+          step={1}
+          min={0}
+          max={32}
+          initial={7}
+        />
+        <Knob<Euclidean, 'rotation3'>
+          moduleId={id}
+          param={11}
+          label="ROT"
+          type="stepped"
+          step={1}
+          min={0}
+          max={31}
+          initial={2}
+        />
+      </div>
+
+      <ModuleInputs>
+        <Socket<Euclidean, 'input', 'clock'>
+          moduleId={id}
+          type="input"
+          index={0}
+          label="CLK"
+        />
+        <Socket<Euclidean, 'input', 'reset'>
+          moduleId={id}
+          type="input"
+          index={1}
+          label="RST"
+        />
+        <Socket<Euclidean, 'parameter', 'steps0'>
+          moduleId={id}
+          type="parameter"
+          index={0}
+          label="STP1"
+        />
+        <Socket<Euclidean, 'parameter', 'pulses0'>
+          moduleId={id}
+          type="parameter"
+          index={1}
+          label="HIT1"
+        />
+        <Socket<Euclidean, 'parameter', 'rotation0'>
+          moduleId={id}
+          type="parameter"
+          index={2}
+          label="ROT1"
+        />
+        <Socket<Euclidean, 'parameter', 'steps1'>
+          moduleId={id}
+          type="parameter"
+          index={3}
+          label="STP2"
+        />
+        <Socket<Euclidean, 'parameter', 'pulses1'>
+          moduleId={id}
+          type="parameter"
+          index={4}
+          label="HIT2"
+        />
+        <Socket<Euclidean, 'parameter', 'rotation1'>
+          moduleId={id}
+          type="parameter"
+          index={5}
+          label="ROT2"
+        />
+        <Socket<Euclidean, 'parameter', 'steps2'>
+          moduleId={id}
+          type="parameter"
+          index={6}
+          label="STP3"
+        />
+        <Socket<Euclidean, 'parameter', 'pulses2'>
+          moduleId={id}
+          type="parameter"
+          index={7}
+          label="HIT3"
+        />
+        <Socket<Euclidean, 'parameter', 'rotation2'>
+          moduleId={id}
+          type="parameter"
+          index={8}
+          label="ROT3"
+        />
+        <Socket<Euclidean, 'parameter', 'steps3'>
+          moduleId={id}
+          type="parameter"
+          index={9}
+          label="STP4"
+        />
+        <Socket<Euclidean, 'parameter', 'pulses3'>
+          moduleId={id}
+          type="parameter"
+          index={10}
+          label="HIT4"
+        />
+        <Socket<Euclidean, 'parameter', 'rotation3'>
+          moduleId={id}
+          type="parameter"
+          index={11}
+          label="ROT4"
+        />
+      </ModuleInputs>
+
+      <ModuleOutputs>
+        <Socket<Euclidean, 'output', 'gate0'>
+          moduleId={id}
+          type="output"
+          index={0}
+          label="G1"
+        />
+        <Socket<Euclidean, 'output', 'gate1'>
+          moduleId={id}
+          type="output"
+          index={1}
+          label="G2"
+        />
+        <Socket<Euclidean, 'output', 'gate2'>
+          moduleId={id}
+          type="output"
+          index={2}
+          label="G3"
+        />
+        <Socket<Euclidean, 'output', 'gate3'>
+          moduleId={id}
+          type="output"
+          index={3}
+          label="G4"
+        />
+      </ModuleOutputs>
+    </Module>
+  )
+}
+
+export default EuclideanNode
@@ -0,0 +1,369 @@
+import Socket from '../module-parts/Socket'
+import Module from '../module-parts/Module'
+import Knob from '../module-parts/Knob'
+import { ModuleInputs, ModuleOutputs } from '../module-parts/ModuleSockets'
+import { FMVoice } from '@modulate/worklets/src/modules'
+import * as styles from './FMVoice.css'
+
+type Props = {
+  id: string
+}
+
+const RATIO_OPTIONS = [0.5, ...Array.from({ length: 16 }, (_, i) => i + 1)].map(
+  (ratio) => ({ label: `${ratio}x`, value: ratio })
+)
+
+const ALGORITHM_OPTIONS = [
+  { label: '4→3→2→1', value: 0 },
+  { label: '(3+4)→2→1', value: 1 },
+  { label: '(3→2+4)→1', value: 2 },
+  { label: '(4→3+2)→1', value: 3 },
+  { label: '2→1, 4→3', value: 4 },
+  { label: '4→1,2,3', value: 5 },
+  { label: '4→3, 1, 2', value: 6 },
+  { label: '1, 2, 3, 4', value: 7 },
+]
+
+const FMVoiceNode = ({ id }: Props) => {
+  return (
+    <Module id={id} type="FMVoice">
+      <div class={styles.fmVoice}>
+        <div class={styles.global}>
+          <Knob<FMVoice, 'cv'>
+            moduleId={id}
+            param={0}
+            label="CV"
+            type="linear"
+            min={-5}
+            max={5}
+            initial={0}
+          />
+          <Knob<FMVoice, 'algorithm'>
+            moduleId={id}
+            param={1}
+            label="ALGO"
+            type="option"
+            options={ALGORITHM_OPTIONS}
+            initial={0}
+          />
+          <Knob<FMVoice, 'feedback'>
+            moduleId={id}
+            param={2}
+            label="FB"
+            type="percentage"
+            initial={0}
+          />
+          <Knob<FMVoice, 'level'>
+            moduleId={id}
+            param={3}
+            label="LVL"
+            type="linear"
+            min={-2}
+            max={2}
+            initial={1}
+          />
+        </div>
+        <div class={styles.operators}>
+          <div class={styles.operator}>
+            <div class={styles.operatorLabel}>OP1</div>
+            <Knob<FMVoice, 'ratio1'>
+              moduleId={id}
+              param={4}
+              label="RATIO"
+              type="option"
+              options={RATIO_OPTIONS}
+              initial={1}
+            />
+            <Knob<FMVoice, 'detune1'>
+              moduleId={id}
+              param={5}
+              label="DTN"
+              type="linear"
+              min={-0.5}
+              max={0.5}
+              initial={0}
+            />
+            <Knob<FMVoice, 'level1'>
+              moduleId={id}
+              param={6}
+              label="LVL"
+              type="percentage"
+              initial={1}
+            />
+            <Knob<FMVoice, 'attack1'>
+              moduleId={id}
+              param={7}
+              label="A"
+              type="exponential"
+              unit="s"
+              exponent={2}
+              min={0.001}
+              max={10}
+              initial={0.01}
+            />
+            <Knob<FMVoice, 'decay1'>
+              moduleId={id}
+              param={8}
+              label="D"
+              type="exponential"
+              unit="s"
+              exponent={2}
+              min={0.001}
+              max={10}
+              initial={0.3}
+            />
+            <Knob<FMVoice, 'sustain1'>
+              moduleId={id}
+              param={9}
+              label="S"
+              type="percentage"
+              initial={0.7}
+            />
+            <Knob<FMVoice, 'release1'>
+              moduleId={id}
+              param={10}
+              label="R"
+              type="exponential"
+              unit="s"
+              exponent={2}
+              min={0.001}
+              max={10}
+              initial={0.3}
+            />
+          </div>
+          <div class={styles.operator}>
+            <div class={styles.operatorLabel}>OP2</div>
+            <Knob<FMVoice, 'ratio2'>
+              moduleId={id}
+              param={11}
+              label="RATIO"
+              type="option"
+              options={RATIO_OPTIONS}
+              initial={1}
+            />
+            <Knob<FMVoice, 'detune2'>
+              moduleId={id}
+              param={12}
+              label="DTN"
+              type="linear"
+              min={-0.5}
+              max={0.5}
+              initial={0}
+            />
+            <Knob<FMVoice, 'level2'>
+              moduleId={id}
+              param={13}
+              label="LVL"
+              type="percentage"
+              initial={0.5}
+            />
+            <Knob<FMVoice, 'attack2'>
+              moduleId={id}
+              param={14}
+              label="A"
+              type="exponential"
+              unit="s"
+              exponent={2}
+              min={0.001}
+              max={10}
+              initial={0.01}
+            />
+            <Knob<FMVoice, 'decay2'>
+              moduleId={id}
+              param={15}
+              label="D"
+              type="exponential"
+              unit="s"
+              exponent={2}
+              min={0.001}
+              max={10}
+              initial={0.3}
+            />
+            <Knob<FMVoice, 'sustain2'>
+              moduleId={id}
+              param={16}
+              label="S"
+              type="percentage"
+              initial={0.7}
+            />
+            <Knob<FMVoice, 'release2'>
+              moduleId={id}
+              param={17}
+              label="R"
+              type="exponential"
+              unit="s"
+              exponent={2}
+              min={0.001}
+              max={10}
+              initial={0.3}
+            />
+          </div>
+          <div class={styles.operator}>
+            <div class={styles.operatorLabel}>OP3</div>
+            <Knob<FMVoice, 'ratio3'>
+              moduleId={id}
+              param={18}
+              label="RATIO"
+              type="option"
+              options={RATIO_OPTIONS}
+              initial={1}
+            />
+            <Knob<FMVoice, 'detune3'>
+              moduleId={id}
+              param={19}
+              label="DTN"
+              type="linear"
+              min={-0.5}
+              max={0.5}
+              initial={0}
+            />
+            <Knob<FMVoice, 'level3'>
+              moduleId={id}
+              param={20}
+              label="LVL"
+              type="percentage"
+              initial={0.5}
+            />
+            <Knob<FMVoice, 'attack3'>
+              moduleId={id}
+              param={21}
+              label="A"
+              type="exponential"
+              unit="s"
+              exponent={2}
+              min={0.001}
+              max={10}
+              initial={0.01}
+            />
+            <Knob<FMVoice, 'decay3'>
+              moduleId={id}
+              param={22}
+              label="D"
+              type="exponential"
+              unit="s"
+              exponent={2}
+              min={0.001}
+              max={10}
+              initial={0.3}
+            />
+            <Knob<FMVoice, 'sustain3'>
+              moduleId={id}
+              param={23}
+              label="S"
+              type="percentage"
+              initial={0.7}
+            />
+            <Knob<FMVoice, 'release3'>
+              moduleId={id}
+              param={24}
+              label="R"
+              type="exponential"
+              unit="s"
+              exponent={2}
+              min={0.001}
+              max={10}
+              initial={0.3}
+            />
+          </div>
+          <div class={styles.operator}>
+            <div class={styles.operatorLabel}>OP4</div>
+            <Knob<FMVoice, 'ratio4'>
+              moduleId={id}
+              param={25}
+              label="RATIO"
+              type="option"
+              options={RATIO_OPTIONS}
+              initial={1}
+            />
+            <Knob<FMVoice, 'detune4'>
+              moduleId={id}
+              param={26}
+              label="DTN"
+              type="linear"
+              min={-0.5}
+              max={0.5}
+              initial={0}
+            />
+            <Knob<FMVoice, 'level4'>
+              moduleId={id}
+              param={27}
+              label="LVL"
+              type="percentage"
+              initial={0.5}
+            />
+            <Knob<FMVoice, 'attack4'>
+              moduleId={id}
+              param={28}
+              label="A"
+              type="exponential"
+              unit="s"
+              exponent={2}
+              min={0.001}
+              max={10}
+              initial={0.01}
+            />
+            <Knob<FMVoice, 'decay4'>
+              moduleId={id}
+              param={29}
+              label="D"
+              type="exponential"
+              unit="s"
+              exponent={2}
+              min={0.001}
+              max={10}
+              initial={0.3}
+            />
+            <Knob<FMVoice, 'sustain4'>
+              moduleId={id}
+              param={30}
+              label="S"
+              type="percentage"
+              initial={0.7}
+            />
+            <Knob<FMVoice, 'release4'>
+              moduleId={id}
+              param={31}
+              label="R"
+              type="exponential"
+              unit="s"
+              exponent={2}
+              min={0.001}
+              max={10}
+              initial={0.3}
+            />
+          </div>
+        </div>
+      </div>
+      <ModuleInputs>
+        <Socket<FMVoice, 'parameter', 'cv'>
+          moduleId={id}
+          type="parameter"
+          index={0}
+          label="CV"
+        />
+        <Socket<FMVoice, 'input', 'gate'>
+          moduleId={id}
+          type="input"
+          index={0}
+          label="GATE"
+        />
+        <Socket<FMVoice, 'parameter', 'feedback'>
+          moduleId={id}
+          type="parameter"
+          index={2}
+          label="FB"
+        />
+      </ModuleInputs>
+      <ModuleOutputs>
+        <Socket<FMVoice, 'output', 'output'>
+          moduleId={id}
+          type="output"
+          index={0}
+          label="OUT"
+        />
+      </ModuleOutputs>
+    </Module>
+  )
+}
+
+export default FMVoiceNode
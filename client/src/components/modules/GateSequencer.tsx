@@ -0,0 +1,294 @@
+import { Component, useEffect } from 'kaiku'
+import { GateStep } from '@modulate/common/types'
+import { GateSequencer } from '@modulate/worklets/src/modules'
+import * as engine from '../../engine'
+import { getModuleState, setModuleState } from '../../state'
+import Socket from '../module-parts/Socket'
+import Module from '../module-parts/Module'
+import Knob from '../module-parts/Knob'
+import { ModuleInputs, ModuleOutputs } from '../module-parts/ModuleSockets'
+import * as styles from './GateSequencer.css'
+
+type Props = {
+  id: string
+}
+
+type GateSequencerTrack = {
+  length: number
+  steps: GateStep[]
+}
+
+type GateSequencerState = {
+  tracks: GateSequencerTrack[]
+}
+
+const NUM_TRACKS = 8
+const MAX_STEPS = 64
+const STEPS_PER_PAGE = 16
+const PAGES = [0, 1, 2, 3]
+
+// Values the step controls cycle through when clicked.
+const VELOCITIES = [1, 0.75, 0.5, 0.25]
+const PROBABILITIES = [1, 0.75, 0.5, 0.25]
+const RATCHETS = [1, 2, 3, 4]
+
+const cycle = (values: number[], value: number) =>
+  values[(values.indexOf(value) + 1) % values.length]!
+
+class GateSequencerNode extends Component<
+  Props,
+  {
+    playheads: number[]
+    page: number
+    editing: { track: number; step: number }
+  }
+> {
+  readPlayheads: (() => Float32Array | null) | null = null
+
+  state = {
+    playheads: Array(NUM_TRACKS).fill(0),
+    page: 0,
+    editing: { track: 0, step: 0 },
+  }
+
+  constructor(props: Props) {
+    super(props)
+
+    if (!getModuleState<GateSequencerState>(props.id)) {
+      setModuleState<GateSequencerState>(props.id, {
+        tracks: Array(NUM_TRACKS)
+          .fill(null)
+          .map(() => ({
+            length: 16,
+            steps: Array(MAX_STEPS)
+              .fill(null)
+              .map(() => ({
+                gate: false,
+                velocity: 1,
+                probability: 1,
+                ratchets: 1,
+              })),
+          })),
+      })
+    }
+
+    engine.getModulePointers(props.id).then((pointers) => {
+      this.readPlayheads = engine.createUiStateReader(pointers[0]!, NUM_TRACKS)
+    })
+
+    useEffect(() => {
+      const interval = setInterval(() => {
+        const playheads = this.readPlayheads?.()
+        if (playheads) {
+          this.state.playheads = Array.from(playheads)
+        }
+      }, 16)
+
+      return () => clearInterval(interval)
+    })
+
+    useEffect(() => {
+      const { tracks } = getModuleState<GateSequencerState>(props.id)
+      tracks.forEach(({ length, steps }, track) => {
+        engine.sendMessageToModule<GateSequencer>(props.id, {
+          type: 'GateSequencerSetTrack',
+          track,
+          length,
+          steps,
+        })
+      })
+    })
+  }
+
+  render({ id }: Props) {
+    const { tracks } = getModuleState<GateSequencerState>(id)
+    const { page, editing, playheads } = this.state
+    const editingTrack = tracks[editing.track]!
+    const editingStep = editingTrack.steps[editing.step]!
+    const firstStep = page * STEPS_PER_PAGE
+
+    return (
+      <Module id={id} type="GateSequencer">
+        <div class={styles.gateSequencer}>
+          <div class={styles.pages}>
+            {PAGES.map((index) => (
+              <button
+                class={[styles.indicator, { [styles.on]: page === index }]}
+                onClick={() => {
+                  this.state.page = index
+                }}
+              />
+            ))}
+          </div>
+          <div class={styles.tracks}>
+            {tracks.map((track, trackIndex) => (
+              <div class={styles.track}>
+                <button
+                  class={styles.lengthButton}
+                  onClick={() => {
+                    track.length = Math.max(1, track.length - 1)
+                  }}
+                >
+                  -
+                </button>
+                <span class={styles.length}>{track.length}</span>
+                <button
+                  class={styles.lengthButton}
+                  onClick={() => {
+                    track.length = Math.min(MAX_STEPS, track.length + 1)
+                  }}
+                >
+                  +
+                </button>
+                {track.steps
+                  .slice(firstStep, firstStep + STEPS_PER_PAGE)
+                  .map((step, offset) => {
+                    const stepIndex = firstStep + offset
+                    return (
+                      <button
+                        class={() => [
+                          styles.step,
+                          {
+                            [styles.on]: step.gate,
+                            [styles.current]:
+                              playheads[trackIndex] === stepIndex,
+                            [styles.editing]:
+                              editing.track === trackIndex &&
+                              editing.step === stepIndex,
+                            [styles.uncertain]: step.probability < 1,
+                          },
+                        ]}
+                        style={{
+                          opacity: String(
+                            stepIndex >= track.length
+                              ? 0.2
+                              : step.gate
+                                ? 0.4 + 0.6 * step.velocity
+                                : 1
+                          ),
+                        }}
+                        onClick={() => {
+                          if (
+                            editing.track === trackIndex &&
+                            editing.step === stepIndex
+                          ) {
+                            step.gate = !step.gate
+                          } else {
+                            this.state.editing = {
+                              track: trackIndex,
+                              step: stepIndex,
+                            }
+                          }
+                        }}
+                      >
+                        {step.ratchets > 1 ? step.ratchets : ''}
+                      </button>
+                    )
+                  })}
+              </div>
+            ))}
+          </div>
+          <div class={styles.stepControls}>
+            <button
+              onClick={() => {
+                editingStep.velocity = cycle(VELOCITIES, editingStep.velocity)
+              }}
+            >
+              VEL {Math.round(editingStep.velocity * 100)}%
+            </button>
+            <button
+              onClick={() => {
+                editingStep.probability = cycle(
+                  PROBABILITIES,
+                  editingStep.probability
+                )
+              }}
+            >
+              PROB {Math.round(editingStep.probability * 100)}%
+            </button>
+            <button
+              onClick={() => {
+                editingStep.ratchets = cycle(RATCHETS, editingStep.ratchets)
+              }}
+            >
+              RATCH {editingStep.ratchets}
+            </button>
+            <Knob<GateSequencer, 'gateLength'>
+              moduleId={id}
+              param={0}
+              label="GATE"
+              type="percentage"
+              initial={0.5}
+            />
+          </div>
+        </div>
+        <ModuleInputs>
+          <Socket<GateSequencer, 'input', 'clock'>
+            moduleId={id}
+            type="input"
+            index={0}
+            label="CLK"
+          />
+          <Socket<GateSequencer, 'input', 'reset'>
+            moduleId={id}
+            type="input"
+            index={1}
+            label="RST"
+          />
+        </ModuleInputs>
+        <ModuleOutputs>
+          <Socket<GateSequencer, 'output', 'gate0'>
+            moduleId={id}
+            type="output"
+            index={0}
+            label="G1"
+          />
+          <Socket<GateSequencer, 'output', 'gate1'>
+            moduleId={id}
+            type="output"
+            index={1}
+            label="G2"
+          />
+          <Socket<GateSequencer, 'output', 'gate2'>
+            moduleId={id}
+            type="output"
+            index={2}
+            label="G3"
+          />
+          <Socket<GateSequencer, 'output', 'gate3'>
+            moduleId={id}
+            type="output"
+            index={3}
+            label="G4"
+          />
+          <Socket<GateSequencer, 'output', 'gate4'>
+            moduleId={id}
+            type="output"
+            index={4}
+            label="G5"
+          />
+          <Socket<GateSequencer, 'output', 'gate5'>
+            moduleId={id}
+            type="output"
+            index={5}
+            label="G6"
+          />
+          <Socket<GateSequencer, 'output', 'gate6'>
+            moduleId={id}
+            type="output"
+            index={6}
+            label="G7"
+          />
+          <Socket<GateSequencer, 'output', 'gate7'>
+            moduleId={id}
+            type="output"
+            index={7}
+            label="G8"
+          />
+        </ModuleOutputs>
+      </Module>
+    )
+  }
+}
+
+export default GateSequencerNode
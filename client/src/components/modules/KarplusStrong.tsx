@@ -0,0 +1,116 @@
+import Socket from '../module-parts/Socket'
+import Module from '../module-parts/Module'
+import Knob from '../module-parts/Knob'
+import ModuleControls from '../module-parts/ModuleControls'
+import { ModuleInputs, ModuleOutputs } from '../module-parts/ModuleSockets'
+import { KarplusStrong } from '@modulate/worklets/src/modules'
+
+type Props = {
+  id: string
+}
+
+const KarplusStrongNode = ({ id }: Props) => {
+  return (
+    <Module id={id} type="KarplusStrong">
+      <ModuleControls>
+        <Knob<KarplusStrong, 'cv'>
+          moduleId={id}
+          param={0}
+          label="CV"
+          type="linear"
+          min={-5}
+          max={5}
+          initial={0}
+        />
+        <Knob<KarplusStrong, 'damping'>
+          moduleId={id}
+          param={1}
+          label="DAMP"
+          type="percentage"
+          initial={0.5}
+        />
+        <Knob<KarplusStrong, 'brightness'>
+          moduleId={id}
+          param={2}
+          label="BRIGHT"
+          type="percentage"
+          initial={0.7}
+        />
+        <Knob<KarplusStrong, 'position'>
+          moduleId={id}
+          param={3}
+          label="POS"
+          type="linear"
+          min={0.02}
+          max={0.5}
+          initial={0.2}
+        />
+        <Knob<KarplusStrong, 'decay'>
+          moduleId={id}
+          param={4}
+          label="DECAY"
+          type="exponential"
+          unit="s"
+          exponent={2}
+          min={0.05}
+          max={20}
+          initial={3}
+        />
+        <Knob<KarplusStrong, 'bow'>
+          moduleId={id}
+          param={5}
+          label="BOW"
+          type="percentage"
+          initial={0}
+        />
+        <Knob<KarplusStrong, 'level'>
+          moduleId={id}
+          param={6}
+          label="LVL"
+          type="linear"
+          min={-2}
+          max={2}
+          initial={1}
+        />
+      </ModuleControls>
+
+      <ModuleInputs>
+        <Socket<KarplusStrong, 'parameter', 'cv'>
+          moduleId={id}
+          type="parameter"
+          index={0}
+          label="CV"
+        />
+        <Socket<KarplusStrong, 'input', 'trigger'>
+          moduleId={id}
+          type="input"
+          index={0}
+          label="TRIG"
+        />
+        <Socket<KarplusStrong, 'input', 'exciter'>
+          moduleId={id}
+          type="input"
+          index={1}
+          label="EXC"
+        />
+        <Socket<KarplusStrong, 'parameter', 'position'>
+          moduleId={id}
+          type="parameter"
+          index={3}
+          label="POS"
+        />
+      </ModuleInputs>
+
+      <ModuleOutputs>
+        <Socket<KarplusStrong, 'output', 'output'>
+          moduleId={id}
+          type="output"
+          index={0}
+          label="OUT"
+        />
+      </ModuleOutputs>
+    </Module>
+  )
+}
+
+export default KarplusStrongNode
@@ -0,0 +1,105 @@
+import Socket from '../module-parts/Socket'
+import Module from '../module-parts/Module'
+import Knob from '../module-parts/Knob'
+import ModuleControls from '../module-parts/ModuleControls'
+import { ModuleInputs, ModuleOutputs } from '../module-parts/ModuleSockets'
+import { ModalResonator } from '@modulate/worklets/src/modules'
+
+type Props = {
+  id: string
+}
+
+const STRUCTURE_OPTIONS = [
+  { label: 'STRING', value: 0 },
+  { label: 'BAR', value: 1 },
+  { label: 'BELL', value: 2 },
+  { label: 'PLATE', value: 3 },
+]
+
+const ModalResonatorNode = ({ id }: Props) => {
+  return (
+    <Module id={id} type="ModalResonator">
+      <ModuleControls>
+        <Knob<ModalResonator, 'cv'>
+          moduleId={id}
+          param={0}
+          label="CV"
+          type="linear"
+          min={-5}
+          max={5}
+          initial={0}
+        />
+        <Knob<ModalResonator, 'structure'>
+          moduleId={id}
+          param={1}
+          label="STRUCT"
+          type="option"
+          options={STRUCTURE_OPTIONS}
+          initial={0}
+        />
+        <Knob<ModalResonator, 'brightness'>
+          moduleId={id}
+          param={2}
+          label="BRIGHT"
+          type="percentage"
+          initial={0.5}
+        />
+        <Knob<ModalResonator, 'damping'>
+          moduleId={id}
+          param={3}
+          label="DAMP"
+          type="percentage"
+          initial={0.5}
+        />
+        <Knob<ModalResonator, 'position'>
+          moduleId={id}
+          param={4}
+          label="POS"
+          type="percentage"
+          initial={0.3}
+        />
+        <Knob<ModalResonator, 'level'>
+          moduleId={id}
+          param={5}
+          label="LVL"
+          type="linear"
+          min={-2}
+          max={2}
+          initial={1}
+        />
+      </ModuleControls>
+
+      <ModuleInputs>
+        <Socket<ModalResonator, 'input', 'input'>
+          moduleId={id}
+          type="input"
+          index={0}
+          label="IN"
+        />
+        <Socket<ModalResonator, 'parameter', 'cv'>
+          moduleId={id}
+          type="parameter"
+          index={0}
+          label="CV"
+        />
+        <Socket<ModalResonator, 'parameter', 'damping'>
+          moduleId={id}
+          type="parameter"
+          index={3}
+          label="DAMP"
+        />
+      </ModuleInputs>
+
+      <ModuleOutputs>
+        <Socket<ModalResonator, 'output', 'output'>
+          moduleId={id}
+          type="output"
+          index={0}
+          label="OUT"
+        />
+      </ModuleOutputs>
+    </Module>
+  )
+}
+
+export default ModalResonatorNode
@@ -0,0 +1,67 @@
+import Socket from '../module-parts/Socket'
+import Module from '../module-parts/Module'
+import Knob from '../module-parts/Knob'
+import { ModuleInputs, ModuleOutputs } from '../module-parts/ModuleSockets'
+import { Noise } from '@modulate/worklets/src/modules'
+
+type Props = {
+  id: string
+}
+
+const NoiseNode = ({ id }: Props) => {
+  return (
+    <Module id={id} type="Noise">
+      <Knob<Noise, 'level'>
+        moduleId={id}
+        param={0}
+        label="LVL"
+        type="linear"
+        min={0}
+        max={2}
+        initial={0.5}
+      />
+      <ModuleInputs>
+        <Socket<Noise, 'parameter', 'level'>
+          moduleId={id}
+          type="parameter"
+          index={0}
+          label="LVL"
+        />
+      </ModuleInputs>
+      <ModuleOutputs>
+        <Socket<Noise, 'output', 'white'>
+          moduleId={id}
+          type="output"
+          index={0}
+          label="WHT"
+        />
+        <Socket<Noise, 'output', 'pink'>
+          moduleId={id}
+          type="output"
+          index={1}
+          label="PNK"
+        />
+        <Socket<Noise, 'output', 'brown'>
+          moduleId={id}
+          type="output"
+          index={2}
+          label="BRN"
+        />
+        <Socket<Noise, 'output', 'blue'>
+          moduleId={id}
+          type="output"
+          index={3}
+          label="BLU"
+        />
+        <Socket<Noise, 'output', 'velvet'>
+          moduleId={id}
+          type="output"
+          index={4}
+          label="VLV"
+        />
+      </ModuleOutputs>
+    </Module>
+  )
+}
+
+export default NoiseNode
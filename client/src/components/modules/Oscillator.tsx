@@ -9,6 +9,12 @@ type Props = {
   id: string
 }
 
+const SYNC_MODE_OPTIONS = [
+  { label: 'HARD', value: 0 },
+  { label: 'REV', value: 1 },
+  { label: 'OFF', value: 2 },
+]
+
 const OscillatorNode = ({ id }: Props) => {
   return (
     <Module id={id} type="Oscillator">
@@ -56,6 +62,58 @@ const OscillatorNode = ({ id }: Props) => {
           max={2}
           initial={1}
         />
+        <Knob<Oscillator, 'linFm'>
+          moduleId={id}
+          param={5}
+          label="LIN FM"
+          type="linear"
+          min={0}
+          max={2000}
+          initial={0}
+        />
+        <Knob<Oscillator, 'pm'>
+          moduleId={id}
+          param={6}
+          label="PM"
+          type="linear"
+          min={-1}
+          max={1}
+          initial={0}
+        />
+        <Knob<Oscillator, 'syncMode'>
+          moduleId={id}
+          param={7}
+          label="SYNC"
+          type="option"
+          options={SYNC_MODE_OPTIONS}
+          initial={0}
+        />
+        <Knob<Oscillator, 'voices'>
+          moduleId={id}
+          param={8}
+          label="VOICES"
+          type="stepped"
+          step={1}
+          min={1}
+          max={16}
+          initial={1}
+        />
+        <Knob<Oscillator, 'detune'>
+          moduleId={id}
+          param={9}
+          label="DETUNE"
+          type="linear"
+          min={0}
+          max={1}
+          initial={0.2}
+        />
+        <Knob<Oscillator, 'spread'>
+          moduleId={id}
+          param={10}
+          label="SPREAD"
+          type="percentage"
+          initial={0.5}
+        />
       </ModuleControls>
 
       <ModuleInputs>
@@ -77,6 +135,18 @@ const OscillatorNode = ({ id }: Props) => {
           index={2}
           label="PW"
         />
+        <Socket<Oscillator, 'parameter', 'linFm'>
+          moduleId={id}
+          type="parameter"
+          index={5}
+          label="LIN FM"
+        />
+        <Socket<Oscillator, 'parameter', 'pm'>
+          moduleId={id}
+          type="parameter"
+          index={6}
+          label="PM"
+        />
         <Socket<Oscillator, 'input', 'sync'>
           moduleId={id}
           type="input"
@@ -110,6 +180,18 @@ const OscillatorNode = ({ id }: Props) => {
           index={3}
           label="SQR"
         />
+        <Socket<Oscillator, 'output', 'left'>
+          moduleId={id}
+          type="output"
+          index={4}
+          label="L"
+        />
+        <Socket<Oscillator, 'output', 'right'>
+          moduleId={id}
+          type="output"
+          index={5}
+          label="R"
+        />
       </ModuleOutputs>
     </Module>
   )
@@ -0,0 +1,129 @@
+import { Component, useEffect } from 'kaiku'
+import { NoteName } from '@modulate/common/types'
+import { Quantizer } from '@modulate/worklets/src/modules'
+import * as engine from '../../engine'
+import { getModuleState, setModuleState } from '../../state'
+import Socket from '../module-parts/Socket'
+import Module from '../module-parts/Module'
+import Knob from '../module-parts/Knob'
+import Keyboard from '../module-parts/Keyboard'
+import { ModuleInputs, ModuleOutputs } from '../module-parts/ModuleSockets'
+import * as styles from './Quantizer.css'
+
+type Props = {
+  id: string
+}
+
+type QuantizerState = {
+  notes: NoteName[]
+}
+
+const NOTE_NAMES: NoteName[] = [
+  'C',
+  'C#',
+  'D',
+  'D#',
+  'E',
+  'F',
+  'F#',
+  'G',
+  'G#',
+  'A',
+  'A#',
+  'B',
+]
+
+const ROOT_OPTIONS = NOTE_NAMES.map((name, index) => ({
+  label: name,
+  value: index,
+}))
+
+class QuantizerNode extends Component<Props> {
+  constructor(props: Props) {
+    super(props)
+
+    if (!getModuleState<QuantizerState>(props.id)) {
+      setModuleState<QuantizerState>(props.id, {
+        notes: ['C', 'D', 'E', 'F', 'G', 'A', 'B'],
+      })
+    }
+
+    useEffect(() => {
+      const { notes } = getModuleState<QuantizerState>(props.id)
+      engine.sendMessageToModule<Quantizer>(props.id, {
+        type: 'QuantizerSetNotes',
+        notes: NOTE_NAMES.map((name) => notes.includes(name)),
+      })
+    })
+  }
+
+  render({ id }: Props) {
+    const quantizerState = getModuleState<QuantizerState>(id)
+
+    return (
+      <Module id={id} type="Quantizer">
+        <div class={styles.quantizer}>
+          <Keyboard
+            note={quantizerState.notes}
+            onChange={(note) => {
+              const { notes } = quantizerState
+              quantizerState.notes = notes.includes(note as NoteName)
+                ? notes.filter((enabled) => enabled !== note)
+                : [...notes, note as NoteName]
+            }}
+          />
+          <div class={styles.knobGroup}>
+            <Knob<Quantizer, 'root'>
+              moduleId={id}
+              param={0}
+              label="ROOT"
+              type="option"
+              options={ROOT_OPTIONS}
+              initial={0}
+            />
+            <Knob<Quantizer, 'transpose'>
+              moduleId={id}
+              param={1}
+              label="TRANS"
+              type="stepped"
+              step={1}
+              min={-24}
+              max={24}
+              initial={0}
+            />
+          </div>
+        </div>
+        <ModuleInputs>
+          <Socket<Quantizer, 'input', 'input'>
+            moduleId={id}
+            type="input"
+            index={0}
+            label="IN"
+          />
+          <Socket<Quantizer, 'parameter', 'transpose'>
+            moduleId={id}
+            type="parameter"
+            index={1}
+            label="TRANS"
+          />
+        </ModuleInputs>
+        <ModuleOutputs>
+          <Socket<Quantizer, 'output', 'cv'>
+            moduleId={id}
+            type="output"
+            index={0}
+            label="CV"
+          />
+          <Socket<Quantizer, 'output', 'trigger'>
+            moduleId={id}
+            type="output"
+            index={1}
+            label="TRIG"
+          />
+        </ModuleOutputs>
+      </Module>
+    )
+  }
+}
+
+export default QuantizerNode
@@ -0,0 +1,86 @@
+import Socket from '../module-parts/Socket'
+import Module from '../module-parts/Module'
+import Knob from '../module-parts/Knob'
+import ModuleControls from '../module-parts/ModuleControls'
+import { ModuleInputs, ModuleOutputs } from '../module-parts/ModuleSockets'
+import { SampleAndHold } from '@modulate/worklets/src/modules'
+
+type Props = {
+  id: string
+}
+
+const MODE_OPTIONS = [
+  { label: 'S&H', value: 0 },
+  { label: 'T&H', value: 1 },
+]
+
+const SampleAndHoldNode = ({ id }: Props) => {
+  return (
+    <Module id={id} type="SampleAndHold">
+      <ModuleControls>
+        <Knob<SampleAndHold, 'mode'>
+          moduleId={id}
+          param={0}
+          label="MODE"
+          type="option"
+          options={MODE_OPTIONS}
+          initial={0}
+        />
+        <Knob<SampleAndHold, 'rate'>
+          moduleId={id}
+          param={1}
+          label="RATE"
+          type="exponential"
+          unit="Hz"
+          exponent={2}
+          min={0.1}
+          max={50}
+          initial={4}
+        />
+        <Knob<SampleAndHold, 'slew'>
+          moduleId={id}
+          param={2}
+          label="SLEW"
+          type="exponential"
+          unit="s"
+          exponent={3}
+          min={0}
+          max={1}
+          initial={0}
+        />
+      </ModuleControls>
+
+      <ModuleInputs>
+        <Socket<SampleAndHold, 'input', 'input'>
+          moduleId={id}
+          type="input"
+          index={0}
+          label="IN"
+        />
+        <Socket<SampleAndHold, 'input', 'trigger'>
+          moduleId={id}
+          type="input"
+          index={1}
+          label="TRIG"
+        />
+        <Socket<SampleAndHold, 'parameter', 'rate'>
+          moduleId={id}
+          type="parameter"
+          index={1}
+          label="RATE"
+        />
+      </ModuleInputs>
+
+      <ModuleOutputs>
+        <Socket<SampleAndHold, 'output', 'output'>
+          moduleId={id}
+          type="output"
+          index={0}
+          label="OUT"
+        />
+      </ModuleOutputs>
+    </Module>
+  )
+}
+
+export default SampleAndHoldNode
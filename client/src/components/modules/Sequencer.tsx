@@ -1,6 +1,6 @@
 import { Component, useEffect } from 'kaiku'
 import * as util from '@modulate/common/util'
-import { NoteName, Note } from '@modulate/common/types'
+import { NoteName, Note, SequencerChainEntry } from '@modulate/common/types'
 import { Sequencer } from '@modulate/worklets/src/modules'
 import * as engine from '../../engine'
 import { getKnobValue, getModuleState, setModuleState } from '../../state'
@@ -18,54 +18,141 @@ type Props = {
 
 const OCTAVES = [8, 7, 6, 5, 4, 3, 2]
 
+const DIRECTION_OPTIONS = [
+  { label: 'FWD', value: 0 },
+  { label: 'REV', value: 1 },
+  { label: 'P-P', value: 2 },
+  { label: 'RAND', value: 3 },
+  { label: 'BRWN', value: 4 },
+]
+
+// Values the step controls cycle through when clicked.
+const PROBABILITIES = [1, 0.75, 0.5, 0.25]
+const RATCHETS = [1, 2, 3, 4]
+const SLIDES = [0, 0.05, 0.1, 0.25, 0.5]
+
+const cycle = (values: number[], value: number) =>
+  values[(values.indexOf(value) + 1) % values.length]!
+
+const DEFAULT_NOTE: Omit<Note, 'index'> = {
+  name: 'C',
+  octave: 4,
+  gate: true,
+  glide: false,
+  probability: 1,
+  ratchets: 1,
+  slide: 0,
+  skip: false,
+  accent: false,
+}
+
+const NUM_PATTERNS = 16
+const MAX_REPEATS = 8
+
+const createPattern = (): Note[] =>
+  Array(32)
+    .fill(null)
+    .map((_, index) => ({ ...DEFAULT_NOTE, index }))
+
 type SequencerState = {
-  notes: Note[]
+  patterns: Note[][]
+  chain: SequencerChainEntry[]
+  chainEnabled: boolean
 }
 
 class SequencerNode extends Component<
   Props,
   {
+    currentPattern: number
     currentStep: number
+    chainPosition: number
+    editingPattern: number
     editing: number
   }
 > {
+  readPlayhead: (() => Float32Array | null) | null = null
+
   state = {
+    currentPattern: 0,
     currentStep: 0,
+    chainPosition: 0,
+    editingPattern: 0,
     editing: 0,
   }
 
   constructor(props: Props) {
     super(props)
 
-    engine.onModuleEvent<Sequencer>(props.id, ({ position }) => {
-      this.state.currentStep = position
-    })
-
-    if (!getModuleState<SequencerState>(props.id)) {
+    const sequencerState = getModuleState<
+      SequencerState & { notes?: Note[] }
+    >(props.id)
+    if (!sequencerState) {
       setModuleState<SequencerState>(props.id, {
-        notes: Array(32)
+        patterns: Array(NUM_PATTERNS).fill(null).map(createPattern),
+        chain: [],
+        chainEnabled: false,
+      })
+    } else {
+      // Patches saved before the pattern banks had a single pattern, and the ones saved before
+      // the per-step settings existed lack them.
+      const patterns = sequencerState.patterns ?? [
+        sequencerState.notes!,
+        ...Array(NUM_PATTERNS - 1)
           .fill(null)
-          .map((_, index) => ({
-            index,
-            name: 'C',
-            octave: 4,
-            gate: true,
-            glide: false,
-          })),
+          .map(createPattern),
+      ]
+      setModuleState<SequencerState>(props.id, {
+        patterns: patterns.map((notes) =>
+          notes.map((note) => ({ ...DEFAULT_NOTE, ...note }))
+        ),
+        chain: sequencerState.chain ?? [],
+        chainEnabled: sequencerState.chainEnabled ?? false,
       })
     }
 
+    engine.getModulePointers(props.id).then((pointers) => {
+      this.readPlayhead = engine.createUiStateReader(pointers[0]!, 3)
+    })
+
+    useEffect(() => {
+      const interval = setInterval(() => {
+        const playhead = this.readPlayhead?.()
+        if (playhead) {
+          this.state.currentPattern = playhead[0]!
+          this.state.currentStep = playhead[1]!
+          this.state.chainPosition = playhead[2]!
+        }
+      }, 16)
+
+      return () => clearInterval(interval)
+    })
+
+    useEffect(() => {
+      const { patterns } = getModuleState<SequencerState>(props.id)
+      patterns.forEach((notes, pattern) => {
+        engine.sendMessageToModule<Sequencer>(props.id, {
+          type: 'SequencerSetNotes',
+          pattern,
+          notes,
+        })
+      })
+    })
+
     useEffect(() => {
-      const { notes } = getModuleState<SequencerState>(props.id)
+      const { chain, chainEnabled } = getModuleState<SequencerState>(props.id)
       engine.sendMessageToModule<Sequencer>(props.id, {
-        type: 'SequencerSetNotes',
-        notes,
+        type: 'SequencerSetChain',
+        chain: chainEnabled ? chain : [],
       })
     })
   }
 
   render({ id }: Props) {
-    const { notes } = getModuleState<SequencerState>(id)
+    const sequencerState = getModuleState<SequencerState>(id)
+    const { patterns, chain } = sequencerState
+    const notes = patterns[this.state.editingPattern]!
+    const isPlayingEditedPattern =
+      this.state.currentPattern === this.state.editingPattern
 
     const groupedNotes = util.splitEvery(util.splitEvery(notes, 4), 4)
     const sequenceLength = getKnobValue<Sequencer, 'length'>(id, 0) ?? 32
@@ -73,6 +160,22 @@ class SequencerNode extends Component<
     return (
       <Module id={id} type="Sequencer">
         <div class={styles.sequencer}>
+          <div class={styles.patterns}>
+            {patterns.map((_, pattern) => (
+              <button
+                class={() => [
+                  styles.indicator,
+                  {
+                    [styles.on]: pattern === this.state.editingPattern,
+                    [styles.current]: pattern === this.state.currentPattern,
+                  },
+                ]}
+                onClick={() => {
+                  this.state.editingPattern = pattern
+                }}
+              />
+            ))}
+          </div>
           <div class={styles.steps}>
             {groupedNotes.map((groups) => (
               <div class={styles.row}>
@@ -85,8 +188,10 @@ class SequencerNode extends Component<
                           {
                             [styles.on]: note.index === this.state.editing,
                             [styles.current]:
+                              isPlayingEditedPattern &&
                               note.index === this.state.currentStep,
-                            [styles.disabled]: note.index >= sequenceLength,
+                            [styles.disabled]:
+                              note.index >= sequenceLength || note.skip,
                           },
                         ]}
                         onClick={() => {
@@ -141,6 +246,72 @@ class SequencerNode extends Component<
               initial={0}
               label="GLIDE"
             />
+            <Knob<Sequencer, 'direction'>
+              moduleId={id}
+              param={2}
+              type="option"
+              options={DIRECTION_OPTIONS}
+              initial={0}
+              label="DIR"
+            />
+            <Knob<Sequencer, 'pattern'>
+              moduleId={id}
+              param={3}
+              type="stepped"
+              step={1}
+              min={0}
+              max={NUM_PATTERNS - 1}
+              initial={0}
+              label="PAT"
+            />
+          </div>
+          <div class={styles.chain}>
+            Chain
+            <button
+              class={[
+                styles.indicator,
+                { [styles.on]: sequencerState.chainEnabled },
+              ]}
+              onClick={() => {
+                sequencerState.chainEnabled = !sequencerState.chainEnabled
+              }}
+            />
+            {chain.map((entry, position) => (
+              <button
+                class={() => [
+                  styles.stepSetting,
+                  {
+                    [styles.current]:
+                      sequencerState.chainEnabled &&
+                      position === this.state.chainPosition,
+                  },
+                ]}
+                onClick={() => {
+                  entry.repeats = (entry.repeats % MAX_REPEATS) + 1
+                }}
+              >
+                {entry.pattern + 1}x{entry.repeats}
+              </button>
+            ))}
+            <button
+              class={styles.stepSetting}
+              onClick={() => {
+                sequencerState.chain = [
+                  ...chain,
+                  { pattern: this.state.editingPattern, repeats: 1 },
+                ]
+              }}
+            >
+              +
+            </button>
+            <button
+              class={styles.stepSetting}
+              onClick={() => {
+                sequencerState.chain = chain.slice(0, -1)
+              }}
+            >
+              -
+            </button>
           </div>
           <div class={styles.noteControls}>
             Gate
@@ -169,6 +340,71 @@ class SequencerNode extends Component<
                   !notes[this.state.editing]!.glide
               }}
             />
+            Skip
+            <button
+              class={[
+                styles.indicator,
+                {
+                  [styles.on]: notes[this.state.editing]!.skip,
+                },
+              ]}
+              onClick={() => {
+                notes[this.state.editing]!.skip =
+                  !notes[this.state.editing]!.skip
+              }}
+            />
+            Accent
+            <button
+              class={[
+                styles.indicator,
+                {
+                  [styles.on]: notes[this.state.editing]!.accent,
+                },
+              ]}
+              onClick={() => {
+                notes[this.state.editing]!.accent =
+                  !notes[this.state.editing]!.accent
+              }}
+            />
+            <button
+              class={styles.stepSetting}
+              onClick={() => {
+                const note = notes[this.state.editing]!
+                note.probability = cycle(PROBABILITIES, note.probability)
+              }}
+            >
+              {Math.round(notes[this.state.editing]!.probability * 100)}%
+            </button>
+            <button
+              class={styles.stepSetting}
+              onClick={() => {
+                const note = notes[this.state.editing]!
+                note.ratchets = cycle(RATCHETS, note.ratchets)
+              }}
+            >
+              x{notes[this.state.editing]!.ratchets}
+            </button>
+            <button
+              class={styles.stepSetting}
+              onClick={() => {
+                const note = notes[this.state.editing]!
+                note.slide = cycle(SLIDES, note.slide)
+              }}
+            >
+              {notes[this.state.editing]!.slide > 0
+                ? `${notes[this.state.editing]!.slide}s`
+                : 'GLIDE'}
+            </button>
+            <button
+              class={styles.stepSetting}
+              onClick={() => {
+                engine.sendMessageToModule<Sequencer>(id, {
+                  type: 'SequencerReset',
+                })
+              }}
+            >
+              RESET
+            </button>
           </div>
         </div>
         <ModuleInputs>
@@ -178,6 +414,12 @@ class SequencerNode extends Component<
             label="GATE"
             index={0}
           />
+          <Socket<Sequencer, 'input', 'reset'>
+            moduleId={id}
+            type="input"
+            label="RST"
+            index={1}
+          />
         </ModuleInputs>
         <ModuleOutputs>
           <Socket<Sequencer, 'output', 'gate'>
@@ -192,6 +434,12 @@ class SequencerNode extends Component<
             label="CV"
             index={0}
           />
+          <Socket<Sequencer, 'output', 'accent'>
+            moduleId={id}
+            type="output"
+            label="ACC"
+            index={2}
+          />
         </ModuleOutputs>
       </Module>
     )
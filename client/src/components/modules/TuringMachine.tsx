@@ -0,0 +1,112 @@
+import Socket from '../module-parts/Socket'
+import Module from '../module-parts/Module'
+import Knob from '../module-parts/Knob'
+import ModuleControls from '../module-parts/ModuleControls'
+import { ModuleInputs, ModuleOutputs } from '../module-parts/ModuleSockets'
+import { TuringMachine } from '@modulate/worklets/src/modules'
+
+type Props = {
+  id: string
+}
+
+const TuringMachineNode = ({ id }: Props) => {
+  return (
+    <Module id={id} type="TuringMachine">
+      <ModuleControls>
+        <Knob<TuringMachine, 'length'>
+          moduleId={id}
+          param={0}
+          label="LEN"
+          type="stepped"
+          step={1}
+          min={2}
+          max={16}
+          initial={8}
+        />
+        <Knob<TuringMachine, 'change'>
+          moduleId={id}
+          param={1}
+          label="CHANGE"
+          type="percentage"
+          initial={0}
+        />
+        <Knob<TuringMachine, 'scale'>
+          moduleId={id}
+          param={2}
+          label="SCALE"
+          type="linear"
+          unit="V"
+          min={0}
+          max={4}
+          initial={1}
+        />
+        <Knob<TuringMachine, 'seed'>
+          moduleId={id}
+          param={3}
+          label="SEED"
+          type="stepped"
+          step={1}
+          min={0}
+          max={99}
+          initial={0}
+        />
+      </ModuleControls>
+
+      <ModuleInputs>
+        <Socket<TuringMachine, 'input', 'clock'>
+          moduleId={id}
+          type="input"
+          index={0}
+          label="CLK"
+        />
+        <Socket<TuringMachine, 'input', 'reset'>
+          moduleId={id}
+          type="input"
+          index={1}
+          label="RST"
+        />
+        <Socket<TuringMachine, 'parameter', 'change'>
+          moduleId={id}
+          type="parameter"
+          index={1}
+          label="CHG"
+        />
+      </ModuleInputs>
+
+      <ModuleOutputs>
+        <Socket<TuringMachine, 'output', 'cv'>
+          moduleId={id}
+          type="output"
+          index={0}
+          label="CV"
+        />
+        <Socket<TuringMachine, 'output', 'gate0'>
+          moduleId={id}
+          type="output"
+          index={1}
+          label="B1"
+        />
+        <Socket<TuringMachine, 'output', 'gate1'>
+          moduleId={id}
+          type="output"
+          index={2}
+          label="B2"
+        />
+        <Socket<TuringMachine, 'output', 'gate2'>
+          moduleId={id}
+          type="output"
+          index={3}
+          label="B3"
+        />
+        <Socket<TuringMachine, 'output', 'gate3'>
+          moduleId={id}
+          type="output"
+          index={4}
+          label="B4"
+        />
+      </ModuleOutputs>
+    </Module>
+  )
+}
+
+export default TuringMachineNode
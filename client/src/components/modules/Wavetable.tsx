@@ -0,0 +1,239 @@
+import { Component, useRef, useEffect } from 'kaiku'
+import * as engine from '../../engine'
+import Socket from '../module-parts/Socket'
+import Module from '../module-parts/Module'
+import Knob from '../module-parts/Knob'
+import Button from '../module-parts/Button'
+import { ModuleInputs, ModuleOutputs } from '../module-parts/ModuleSockets'
+import { Wavetable } from '@modulate/worklets/src/modules'
+import * as styles from './Wavetable.css'
+import { getModuleState, setModuleState } from '../../state'
+
+type Props = {
+  id: string
+}
+
+type WavetableState = {
+  table: TableName
+}
+
+const FRAME_LENGTH = 2048
+
+const generateFrames = (
+  frameCount: number,
+  generate: (frame: number, phase: number) => number
+): Float32Array[] =>
+  Array.from({ length: frameCount }, (_, frame) =>
+    Float32Array.from({ length: FRAME_LENGTH }, (_, i) =>
+      generate(frame, i / FRAME_LENGTH)
+    )
+  )
+
+const TABLES = {
+  Basic: () =>
+    generateFrames(4, (frame, phase) => {
+      switch (frame) {
+        case 0:
+          return Math.sin(phase * Math.PI * 2)
+        case 1:
+          return 1 - 4 * Math.abs(((phase + 0.75) % 1) - 0.5)
+        case 2:
+          return 2 * ((phase + 0.5) % 1) - 1
+        default:
+          return phase < 0.5 ? 1 : -1
+      }
+    }),
+  Pulse: () =>
+    generateFrames(16, (frame, phase) =>
+      phase < 0.5 - (frame / 16) * 0.45 ? 1 : -1
+    ),
+  Harmonics: () =>
+    generateFrames(16, (frame, phase) => {
+      let value = 0
+      for (let harmonic = 1; harmonic <= frame + 1; harmonic++) {
+        value += Math.sin(phase * Math.PI * 2 * harmonic) / (frame + 1)
+      }
+      return value
+    }),
+}
+
+type TableName = keyof typeof TABLES
+
+class WavetableNode extends Component<Props> {
+  frames: Float32Array[] | null = null
+  position: Float32Array | null = null
+  animationFrameRequest: number = 0
+  canvasRef = useRef<HTMLCanvasElement>()
+
+  constructor(props: Props) {
+    super(props)
+
+    this.drawFrame()
+
+    engine.getModulePointers(props.id).then((pointers) => {
+      this.position = new Float32Array(
+        engine.getMemory().buffer,
+        pointers[0],
+        1
+      )
+    })
+
+    if (!getModuleState<WavetableState>(props.id)) {
+      setModuleState<WavetableState>(props.id, {
+        table: 'Basic',
+      })
+    }
+
+    useEffect(() => {
+      const { table } = getModuleState<WavetableState>(props.id)
+      this.loadTable(table)
+    })
+  }
+
+  componentWillUnmount() {
+    cancelAnimationFrame(this.animationFrameRequest)
+  }
+
+  loadTable = (table: TableName) => {
+    this.frames = TABLES[table]()
+
+    const samples = new Float32Array(this.frames.length * FRAME_LENGTH)
+    this.frames.forEach((frame, i) => samples.set(frame, i * FRAME_LENGTH))
+
+    engine.loadWavetable(
+      this.props.id,
+      samples,
+      FRAME_LENGTH,
+      this.frames.length
+    )
+  }
+
+  drawFrame = () => {
+    this.animationFrameRequest = requestAnimationFrame(this.drawFrame)
+
+    const canvas = this.canvasRef.current
+    if (!canvas || !this.frames) return
+
+    const context = canvas.getContext('2d')!
+    context.clearRect(0, 0, canvas.width, canvas.height)
+
+    const position = this.position?.[0] ?? 0
+    const frame = Math.min(Math.floor(position), this.frames.length - 1)
+    const nextFrame = Math.min(frame + 1, this.frames.length - 1)
+    const t = position - frame
+
+    context.strokeStyle = '#ccc'
+    context.beginPath()
+    for (let x = 0; x < canvas.width; x++) {
+      const i = Math.floor((x / canvas.width) * FRAME_LENGTH)
+      const value =
+        this.frames[frame]![i]! * (1 - t) + this.frames[nextFrame]![i]! * t
+      const y = canvas.height / 2 - (value * 0.9 * canvas.height) / 2
+      if (x === 0) {
+        context.moveTo(x, y)
+      } else {
+        context.lineTo(x, y)
+      }
+    }
+    context.stroke()
+  }
+
+  render({ id }: Props) {
+    const wavetableState = getModuleState<WavetableState>(id)
+
+    return (
+      <Module id={id} type="Wavetable">
+        <div class={styles.wavetable}>
+          <canvas ref={this.canvasRef} width="300" height="80" />
+          <div class={styles.tables}>
+            {(Object.keys(TABLES) as TableName[]).map((table) => (
+              <Button
+                class={{ [styles.selected]: wavetableState.table === table }}
+                onClick={() => {
+                  wavetableState.table = table
+                }}
+              >
+                {table}
+              </Button>
+            ))}
+          </div>
+          <div class={styles.knobGroup}>
+            <Knob<Wavetable, 'cv'>
+              moduleId={id}
+              param={0}
+              label="CV"
+              type="linear"
+              min={-5}
+              max={5}
+              initial={0}
+            />
+            <Knob<Wavetable, 'fm'>
+              moduleId={id}
+              param={1}
+              label="FM"
+              type="linear"
+              min={-1}
+              max={1}
+              initial={0}
+            />
+            <Knob<Wavetable, 'fine'>
+              moduleId={id}
+              param={2}
+              label="FINE"
+              type="linear"
+              min={-1}
+              max={1}
+              initial={0}
+            />
+            <Knob<Wavetable, 'position'>
+              moduleId={id}
+              param={3}
+              label="POS"
+              type="percentage"
+              initial={0}
+            />
+            <Knob<Wavetable, 'level'>
+              moduleId={id}
+              param={4}
+              label="LVL"
+              type="linear"
+              min={-2}
+              max={2}
+              initial={1}
+            />
+          </div>
+        </div>
+        <ModuleInputs>
+          <Socket<Wavetable, 'parameter', 'cv'>
+            moduleId={id}
+            type="parameter"
+            index={0}
+            label="CV"
+          />
+          <Socket<Wavetable, 'parameter', 'fm'>
+            moduleId={id}
+            type="parameter"
+            index={1}
+            label="FM"
+          />
+          <Socket<Wavetable, 'parameter', 'position'>
+            moduleId={id}
+            type="parameter"
+            index={3}
+            label="POS"
+          />
+        </ModuleInputs>
+        <ModuleOutputs>
+          <Socket<Wavetable, 'output', 'out'>
+            moduleId={id}
+            type="output"
+            index={0}
+            label="OUT"
+          />
+        </ModuleOutputs>
+      </Module>
+    )
+  }
+}
+
+export default WavetableNode
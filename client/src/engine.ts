@@ -5,6 +5,7 @@ import {
   EngineRequest,
   EngineResponse,
   ModuleEvent,
+  ModuleFault,
   ModuleMessage,
 } from '@modulate/common/types'
 import * as util from '@modulate/common/util'
@@ -23,8 +24,31 @@ type InitOptions = {
 
 let engine: Engine | null = null
 
-const eventSubscriptions: Map<number, (event: ModuleEvent<Module>) => void> =
-  new Map()
+type EventTime = { quantum: number; sampleOffset: number }
+
+let moduleFaultHandler:
+  | ((moduleId: string, fault: ModuleFault) => void)
+  | null = null
+
+export const clearModuleFault = async (moduleId: string) => {
+  assert(engine)
+  const moduleHandle = await getModuleHandle(moduleId)
+  assert(moduleHandle !== null)
+  await engine.clearModuleFault({ moduleHandle })
+}
+
+// Faults are handled in one place for all of the modules instead of through
+// `onModuleEvent`.
+export const onModuleFault = (
+  handler: (moduleId: string, fault: ModuleFault) => void
+) => {
+  moduleFaultHandler = handler
+}
+
+const eventSubscriptions: Map<
+  number,
+  (event: ModuleEvent<Module>, time: EventTime) => void
+> = new Map()
 
 const DEFAULT_OPTIONS: InitOptions = {
   spawnAudioWorklet: true,
@@ -62,10 +86,23 @@ export const initializeEngine = async (opts: Partial<InitOptions> = {}) => {
     data: msg,
   }: MessageEvent<EngineResponse<EngineMessageType> | EngineEvent>) => {
     if (msg.type === 'moduleEvent') {
+      if (msg.message.type === 'ModuleFault') {
+        const moduleId = moduleIdsByHandle.get(msg.moduleHandle)
+        if (moduleId !== undefined) {
+          moduleFaultHandler?.(moduleId, msg.message)
+        }
+        return
+      }
+
+      // Events are queued before they are delivered, so the module may have
+      // been deleted in between, and not every module listens to its events.
       const callback = eventSubscriptions.get(msg.moduleHandle)
-      assert(callback, `unhandled module message: ${JSON.stringify(msg)}`)
+      if (!callback) return
 
-      callback(msg.message)
+      callback(msg.message, {
+        quantum: msg.quantum,
+        sampleOffset: msg.sampleOffset,
+      })
       return
     }
 
@@ -131,6 +168,16 @@ export const initializeEngine = async (opts: Partial<InitOptions> = {}) => {
     connectToParameter: createEngineMethod('connectToParameter'),
     removeConnection: createEngineMethod('removeConnection'),
     sendMessageToModule: createEngineMethod('sendMessageToModule'),
+    clearModuleFault: createEngineMethod('clearModuleFault'),
+    loadWavetable: createEngineMethod('loadWavetable'),
+    setMasterBus: createEngineMethod('setMasterBus'),
+    resetIntegratedLoudness: createEngineMethod('resetIntegratedLoudness'),
+    armRecording: createEngineMethod('armRecording'),
+    startRecording: createEngineMethod('startRecording'),
+    stopRecording: createEngineMethod('stopRecording'),
+    exportRecording: createEngineMethod('exportRecording'),
+    setReferenceFrequency: createEngineMethod('setReferenceFrequency'),
+    setTuning: createEngineMethod('setTuning'),
     getModulePointers: createEngineMethod('getModulePointers'),
     memory,
     pointers,
@@ -228,6 +275,105 @@ export const getWorkerPosition = () => {
   return workerPositionBuf[0]!
 }
 
+// Amount of events dropped because the engine's event queue was full.
+let eventOverflowCountBuf: Uint32Array | null = null
+export const getEventOverflowCount = () => {
+  assert(engine)
+
+  if (!eventOverflowCountBuf) {
+    eventOverflowCountBuf = new Uint32Array(
+      engine.memory.buffer,
+      engine.pointers.eventOverflowCount,
+      1
+    )
+  }
+
+  return Atomics.load(eventOverflowCountBuf, 0)
+}
+
+// Enables the DC blocker and limiter of the master output. The ceiling of the
+// limiter is in dBFS.
+export const setMasterBus = async (enabled: boolean, ceiling: number) => {
+  assert(engine)
+  await engine.setMasterBus({ enabled, ceiling })
+}
+
+// Starts measuring the integrated loudness of the master output over again.
+export const resetIntegratedLoudness = async () => {
+  assert(engine)
+  await engine.resetIntegratedLoudness({})
+}
+
+// Keeps the last `preRollSeconds` of the master output around, so that they end
+// up at the start of the next recording. The pre-roll is at most 30 seconds.
+// Throws while recording.
+export const armRecording = async (preRollSeconds: number) => {
+  assert(engine)
+  const { error } = await engine.armRecording({ preRollSeconds })
+  if (error !== null) {
+    throw new Error(`armRecording: ${error}`)
+  }
+}
+
+export const startRecording = async () => {
+  assert(engine)
+  await engine.startRecording({})
+}
+
+export const stopRecording = async () => {
+  assert(engine)
+  await engine.stopRecording({})
+}
+
+// Stops the recording and hands it over as a WAV file. The engine lets go of
+// the recording afterwards. `truncated` is set when the recording was cut
+// short, after 20 minutes or when the engine fell behind in collecting it.
+export const exportRecording = async () => {
+  assert(engine)
+  const { wav, truncated } = await engine.exportRecording({})
+  return { wav: new Blob([wav], { type: 'audio/wav' }), truncated }
+}
+
+// Frequency in Hz of the reference note, A4, which plays at 0V. Throws if the
+// frequency is outside of 1-2000 Hz.
+export const setReferenceFrequency = async (freq: number) => {
+  assert(engine)
+  const { error } = await engine.setReferenceFrequency({ freq })
+  if (error !== null) {
+    throw new Error(`setReferenceFrequency: ${error}`)
+  }
+}
+
+// Retunes the engine with the contents of a Scala scale (.scl) and keyboard
+// mapping (.kbm) file. Without a scale the tuning goes back to 12 tone equal
+// temperament. Throws if either of the files can't be parsed.
+export const setTuning = async (
+  scl: string | null,
+  kbm: string | null = null
+) => {
+  assert(engine)
+  const { error } = await engine.setTuning({ scl, kbm })
+  if (error !== null) {
+    throw new Error(`setTuning: ${error}`)
+  }
+}
+
+// Amount of frames recorded so far.
+let recordedFramesBuf: BigUint64Array | null = null
+export const getRecordedFrames = () => {
+  assert(engine)
+
+  if (!recordedFramesBuf) {
+    recordedFramesBuf = new BigUint64Array(
+      engine.memory.buffer,
+      engine.pointers.recordedFrames,
+      1
+    )
+  }
+
+  return recordedFramesBuf[0]!
+}
+
 export const getMemory = () => {
   assert(engine)
   return engine.memory
@@ -245,6 +391,7 @@ export const getWorkerTimers = () => {
 }
 
 const moduleHandles: Map<string, Promise<number>> = new Map()
+const moduleIdsByHandle: Map<number, string> = new Map()
 const cableHandles: Map<string, number> = new Map()
 
 export const getModuleHandle = async (
@@ -264,7 +411,10 @@ export const createModule = (moduleId: string, name: ModuleName) => {
 
   moduleHandles.set(
     moduleId,
-    engine.createModule({ name }).then(({ moduleHandle }) => moduleHandle)
+    engine.createModule({ name }).then(({ moduleHandle }) => {
+      moduleIdsByHandle.set(moduleHandle, moduleId)
+      return moduleHandle
+    })
   )
 }
 
@@ -274,9 +424,23 @@ export const deleteModule = async (moduleId: string) => {
   assert(engine)
   await engine.deleteModule({ moduleHandle })
   eventSubscriptions.delete(moduleHandle)
+  moduleIdsByHandle.delete(moduleHandle)
   moduleHandles.delete(moduleId)
 }
 
+// The frames are concatenated in `samples`, each of them `length` samples long.
+export const loadWavetable = async (
+  moduleId: string,
+  samples: Float32Array,
+  length: number,
+  frames: number
+) => {
+  const moduleHandle = await moduleHandles.get(moduleId)
+  assert(typeof moduleHandle !== 'undefined')
+  assert(engine)
+  await engine.loadWavetable({ moduleHandle, samples, length, frames })
+}
+
 export const getModulePointers = async (moduleId: string) => {
   const moduleHandle = await moduleHandles.get(moduleId)
   assert(typeof moduleHandle !== 'undefined')
@@ -359,16 +523,18 @@ export const sendMessageToModule = async <M extends Module>(
   engine.sendMessageToModule({ moduleHandle, message: messageCopy })
 }
 
+// Events arrive in the order they happened. The time of an event can be
+// compared with `getWorkerPosition` to line it up with what is being heard.
 export const onModuleEvent = async <M extends Module>(
   moduleId: string,
-  callback: (event: ModuleEvent<M>) => void
+  callback: (event: ModuleEvent<M>, time: EventTime) => void
 ) => {
   assert(engine)
   const moduleHandle = await getModuleHandle(moduleId)
   assert(moduleHandle !== null)
   eventSubscriptions.set(
     moduleHandle,
-    callback as (event: ModuleEvent<Module>) => void
+    callback as (event: ModuleEvent<Module>, time: EventTime) => void
   )
 }
 
@@ -383,6 +549,32 @@ export const getMemorySlice = (address: number, length: number) => {
   return new Float32Array(engine.memory.buffer, address, length)
 }
 
+const MAX_UI_STATE_READ_ATTEMPTS = 4
+
+// Reads a module's `UiState` (see `ui_state.rs`), which is a sequence counter
+// followed by `length` f32s. The read is retried if the module was writing the
+// state at the same time, and gives null if it didn't succeed, in which case the
+// previous state can be kept around.
+export const createUiStateReader = (address: number, length: number) => {
+  assert(engine)
+  const sequence = new Int32Array(engine.memory.buffer, address, 1)
+  const data = new Float32Array(engine.memory.buffer, address + 4, length)
+  const state = new Float32Array(length)
+
+  return (): Float32Array | null => {
+    for (let i = 0; i < MAX_UI_STATE_READ_ATTEMPTS; i++) {
+      const before = Atomics.load(sequence, 0)
+      if (before % 2 !== 0) continue
+
+      state.set(data)
+
+      if (Atomics.load(sequence, 0) === before) return state
+    }
+
+    return null
+  }
+}
+
 export const getFilterCoefficients = (
   type: FilterType,
   freq: number,
@@ -138,7 +138,7 @@ export default createConfig({
   },
   Oscillator: {
     category: ModuleCategory.GENERATOR,
-    width: 260,
+    width: 580,
     height: 100,
   },
   PowShaper: {
@@ -153,8 +153,8 @@ export default createConfig({
   },
   Sequencer: {
     category: ModuleCategory.SEQUENCER,
-    width: 340,
-    height: 200,
+    width: 420,
+    height: 260,
   },
   VirtualController: {
     category: ModuleCategory.UTILITY,
@@ -197,4 +197,64 @@ export default createConfig({
     height: 140,
     hidden: true,
   },
+  Wavetable: {
+    category: ModuleCategory.GENERATOR,
+    width: 340,
+    height: 200,
+  },
+  FMVoice: {
+    category: ModuleCategory.GENERATOR,
+    width: 460,
+    height: 320,
+  },
+  KarplusStrong: {
+    category: ModuleCategory.GENERATOR,
+    width: 420,
+    height: 100,
+  },
+  ModalResonator: {
+    category: ModuleCategory.FILTER,
+    width: 380,
+    height: 100,
+  },
+  Noise: {
+    category: ModuleCategory.GENERATOR,
+    width: 140,
+    height: 120,
+  },
+  SampleAndHold: {
+    category: ModuleCategory.UTILITY,
+    width: 240,
+    height: 100,
+  },
+  Chaos: {
+    category: ModuleCategory.GENERATOR,
+    width: 200,
+    height: 140,
+  },
+  Quantizer: {
+    category: ModuleCategory.UTILITY,
+    width: 320,
+    height: 180,
+  },
+  Arpeggiator: {
+    category: ModuleCategory.SEQUENCER,
+    width: 260,
+    height: 220,
+  },
+  GateSequencer: {
+    category: ModuleCategory.SEQUENCER,
+    width: 480,
+    height: 260,
+  },
+  Euclidean: {
+    category: ModuleCategory.SEQUENCER,
+    width: 260,
+    height: 360,
+  },
+  TuringMachine: {
+    category: ModuleCategory.SEQUENCER,
+    width: 280,
+    height: 140,
+  },
 })
@@ -21,3 +21,15 @@ export { default as Chorus } from './components/modules/Chorus'
 export { default as EQ3 } from './components/modules/EQ3'
 export { default as RingMod } from './components/modules/RingMod'
 export { default as Sideq } from './components/modules/Sideq'
+export { default as Wavetable } from './components/modules/Wavetable'
+export { default as FMVoice } from './components/modules/FMVoice'
+export { default as KarplusStrong } from './components/modules/KarplusStrong'
+export { default as ModalResonator } from './components/modules/ModalResonator'
+export { default as Noise } from './components/modules/Noise'
+export { default as SampleAndHold } from './components/modules/SampleAndHold'
+export { default as Chaos } from './components/modules/Chaos'
+export { default as Quantizer } from './components/modules/Quantizer'
+export { default as Arpeggiator } from './components/modules/Arpeggiator'
+export { default as GateSequencer } from './components/modules/GateSequencer'
+export { default as Euclidean } from './components/modules/Euclidean'
+export { default as TuringMachine } from './components/modules/TuringMachine'
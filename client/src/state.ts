@@ -36,6 +36,7 @@ const state = createState<State>({
     content: '',
   },
   activeModule: null,
+  moduleFaults: {},
 
   overlay: 'init',
 
@@ -71,6 +72,10 @@ export const nextId = () => {
   return btoa(String.fromCharCode(...randomBytes)) as Id
 }
 
+engine.onModuleFault((moduleId, fault) => {
+  state.moduleFaults[moduleId] = fault
+})
+
 window.addEventListener('popstate', () => {
   state.route = parseRoute(location)
 })
@@ -261,6 +266,11 @@ export const resetModuleKnobs = (moduleId: string) => {
   module.knobs = []
 }
 
+export const clearModuleFault = async (moduleId: string) => {
+  await engine.clearModuleFault(moduleId)
+  delete state.moduleFaults[moduleId]
+}
+
 export const isOwnPatch = () => {
   if (!state.user) return false
   if (!state.patchMetadata.author) return true
@@ -544,5 +554,6 @@ export const deleteModule = async (moduleId: string) => {
   )
 
   delete state.sockets[moduleId]
+  delete state.moduleFaults[moduleId]
   delete state.patch.modules[moduleId]
 }
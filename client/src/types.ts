@@ -9,6 +9,7 @@ import {
   EngineResponse,
   Socket,
   ContextPointers,
+  ModuleFault,
 } from '@modulate/common/types'
 
 export type Route =
@@ -78,6 +79,8 @@ export type State = {
     content: string
   }
   activeModule: Id | null
+  // The latest fault of each module which has faulted.
+  moduleFaults: Record<Id, ModuleFault>
   user: User | null
 
   cursor: Vec2
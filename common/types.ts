@@ -101,6 +101,24 @@ export type Note = {
   octave: number
   gate: boolean
   glide: boolean
+  probability: number
+  ratchets: number
+  // Glide time of the step in seconds, 0 to use the glide time of the sequencer.
+  slide: number
+  skip: boolean
+  accent: boolean
+}
+
+export type GateStep = {
+  gate: boolean
+  velocity: number
+  probability: number
+  ratchets: number
+}
+
+export type SequencerChainEntry = {
+  pattern: number
+  repeats: number
 }
 
 export type SequencerMessage = {
@@ -124,6 +142,10 @@ export type ContextPointers = {
   audioWorkletPosition: number
   workerPerformance: number
   workerPosition: number
+  masterMeter: number
+  masterBusGainReduction: number
+  recordedFrames: number
+  eventOverflowCount: number
 }
 
 export type EngineMessage =
@@ -179,6 +201,61 @@ export type EngineMessage =
       req: { connectionId: number }
       res: {}
     }
+  | {
+      type: 'clearModuleFault'
+      req: { moduleHandle: number }
+      res: {}
+    }
+  | {
+      type: 'loadWavetable'
+      req: {
+        moduleHandle: number
+        samples: Float32Array
+        length: number
+        frames: number
+      }
+      res: {}
+    }
+  | {
+      type: 'setMasterBus'
+      req: { enabled: boolean; ceiling: number }
+      res: {}
+    }
+  | {
+      type: 'resetIntegratedLoudness'
+      req: {}
+      res: {}
+    }
+  | {
+      type: 'armRecording'
+      req: { preRollSeconds: number }
+      res: { error: string | null }
+    }
+  | {
+      type: 'startRecording'
+      req: {}
+      res: {}
+    }
+  | {
+      type: 'stopRecording'
+      req: {}
+      res: {}
+    }
+  | {
+      type: 'exportRecording'
+      req: {}
+      res: { wav: Uint8Array; truncated: boolean }
+    }
+  | {
+      type: 'setReferenceFrequency'
+      req: { freq: number }
+      res: { error: string | null }
+    }
+  | {
+      type: 'setTuning'
+      req: { scl: string | null; kbm: string | null }
+      res: { error: string | null }
+    }
   | {
       type: 'sendMessageToModule'
       req: { moduleHandle: number; message: ModuleMessage<Module> }
@@ -188,14 +265,24 @@ export type EngineMessage =
 export type ModuleMessage<M extends Module> = M extends { messages: any }
   ? M['messages']
   : never
-export type ModuleEvent<M extends Module> = M extends { events: any }
-  ? M['events']
-  : never
+// Any module can fault, see `module_guard.rs`.
+export type ModuleFault = {
+  type: 'ModuleFault'
+  reason: 'NonFinite' | 'Panic'
+  count: number
+}
+
+export type ModuleEvent<M extends Module> =
+  | (M extends { events: any } ? M['events'] : never)
+  | ModuleFault
 
 export type EngineEvent = {
   type: 'moduleEvent'
   moduleHandle: number
   message: ModuleEvent<Module>
+  // The quantum and the sample within it at which the event happened.
+  quantum: number
+  sampleOffset: number
 }
 
 export type EngineMessageType = EngineMessage['type']
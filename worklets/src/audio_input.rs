@@ -9,6 +9,7 @@ use crate::{
 const EMPTY_AUDIO_OUTPUT: &AudioOutput = &(AudioOutput {
   buffers: [AudioBuffer([0.0; QUANTUM_SIZE]); AUDIO_OUTPUT_NUM_BUFFERS],
   current: 0,
+  connections: 0,
 });
 
 #[derive(PartialEq)]
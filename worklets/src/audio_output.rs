@@ -5,6 +5,7 @@ use crate::{audio_buffer::AudioBuffer, modulate_core::AUDIO_OUTPUT_NUM_BUFFERS};
 pub struct AudioOutput {
   pub buffers: [AudioBuffer; AUDIO_OUTPUT_NUM_BUFFERS],
   pub current: usize,
+  pub connections: usize,
 }
 
 impl Index<usize> for AudioOutput {
@@ -25,6 +26,7 @@ impl Default for AudioOutput {
     AudioOutput {
       buffers: [AudioBuffer::default(); AUDIO_OUTPUT_NUM_BUFFERS],
       current: 0,
+      connections: 0,
     }
   }
 }
@@ -42,6 +44,12 @@ impl AudioOutput {
     &mut self.buffers[self.current]
   }
 
+  // Whether the output is connected to any input or parameter, so modules can skip producing
+  // outputs which are not used.
+  pub fn is_connected(&self) -> bool {
+    self.connections > 0
+  }
+
   pub fn read_buffer(&self) -> &AudioBuffer {
     let prev = (self.current + AUDIO_OUTPUT_NUM_BUFFERS - 1) % AUDIO_OUTPUT_NUM_BUFFERS;
     &self.buffers[prev]
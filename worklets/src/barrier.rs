@@ -37,15 +37,24 @@ impl Barrier {
       false
     } else {
       func();
-      self.count.store(0, std::sync::atomic::Ordering::Release);
-      self.generation_id.store(
-        local_gen.wrapping_add(1),
-        std::sync::atomic::Ordering::Release,
-      );
-      unsafe {
-        memory_atomic_notify(self.generation_id.as_ptr(), u32::MAX);
-      }
+      self.release();
       true
     }
   }
+
+  // Lets the waiting threads through. Only the leader, the last thread to arrive, may call this,
+  // which it does after its `wait_and_do` function has run.
+  pub fn release(&self) {
+    let local_gen = self
+      .generation_id
+      .load(std::sync::atomic::Ordering::Acquire);
+    self.count.store(0, std::sync::atomic::Ordering::Release);
+    self.generation_id.store(
+      local_gen.wrapping_add(1),
+      std::sync::atomic::Ordering::Release,
+    );
+    unsafe {
+      memory_atomic_notify(self.generation_id.as_ptr(), u32::MAX);
+    }
+  }
 }
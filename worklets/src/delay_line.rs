@@ -23,8 +23,12 @@ impl VariableDelayLineInterpolated {
   }
 
   pub fn set_delay(&mut self, delay: f32) {
-    assert!(delay < self.size as f32);
-    let delay = delay.max(1.0);
+    // The delay is usually driven by a modulatable parameter, so clamp it to the buffer instead of
+    // asserting, and keep the previous delay when it isn't a number.
+    if delay.is_nan() {
+      return;
+    }
+    let delay = delay.clamp(1.0, (self.size - 1) as f32);
 
     if (delay - self.delay).abs() < f32::EPSILON {
       return;
@@ -35,20 +39,14 @@ impl VariableDelayLineInterpolated {
   }
 
   fn read_pos(&self) -> f32 {
-    let mut read_pos = self.write_pos as f32 - self.current_delay;
-    if read_pos < 0.0 {
-      read_pos += self.size as f32;
+    let read_pos = (self.write_pos as f32 - self.current_delay).rem_euclid(self.size as f32);
 
-      // Due to floating point precision this _is_ a possible case
-      if read_pos >= self.size as f32 {
-        read_pos = 0.0;
-      }
+    // Due to floating point precision the position can round up to the size of the buffer.
+    if read_pos < self.size as f32 {
+      read_pos
+    } else {
+      0.0
     }
-
-    assert!(read_pos >= 0.0);
-    assert!(read_pos < self.size as f32);
-
-    read_pos
   }
 
   pub fn read_sinc(&self) -> f32 {
@@ -73,6 +71,10 @@ impl VariableDelayLineInterpolated {
     lerp(curr, next, read_pos_fract)
   }
 
+  pub fn reset(&mut self) {
+    self.buffer.fill(0.0);
+  }
+
   pub fn write(&mut self, input: f32) {
     self.buffer[self.write_pos] = input;
 
@@ -82,7 +84,8 @@ impl VariableDelayLineInterpolated {
       self.current_delay += self.read_speed;
     }
 
-    assert!(self.current_delay >= 0.0);
+    // Keep rounding errors from taking the delay out of the buffer.
+    self.current_delay = self.current_delay.clamp(0.0, (self.size - 1) as f32);
 
     self.write_pos += 1;
 
@@ -111,7 +114,7 @@ impl VariableDelayLine {
   }
 
   pub fn set_delay(&mut self, delay: usize) {
-    assert!(delay < self.size);
+    let delay = delay.min(self.size - 1);
 
     let mut read_pos = self.write_pos as i32 - delay as i32;
     if read_pos < 0 {
@@ -124,6 +127,10 @@ impl VariableDelayLine {
     self.buffer[self.read_pos]
   }
 
+  pub fn reset(&mut self) {
+    self.buffer.fill(0.0);
+  }
+
   pub fn write(&mut self, input: f32) {
     self.buffer[self.write_pos] = input;
 
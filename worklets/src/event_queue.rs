@@ -0,0 +1,136 @@
+use serde::Serialize;
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use crate::module::{ModuleEvent, ModuleId};
+
+#[derive(Clone, Copy, Serialize)]
+pub struct QueuedEvent {
+  pub quantum: u64,
+  // Sample within the quantum at which the event happened.
+  pub sample_offset: usize,
+  pub id: ModuleId,
+  pub event: ModuleEvent,
+}
+
+// Events of all of the modules in the order they happened, from the audio thread to the main
+// worker. The slots are allocated up front, so pushing never allocates, and events pushed while
+// the queue is full are dropped and counted instead.
+//
+// Only one thread may push at a time and only one may pop at a time. The engine pushes with the
+// module store locked, which keeps the barrier leader and the main worker from pushing at the same
+// time, and pops without the lock from the main worker.
+pub struct EventQueue {
+  slots: Box<[UnsafeCell<Option<QueuedEvent>>]>,
+  // Amount of events pushed and popped so far, wrapping around. Their difference is the amount of
+  // events in the queue.
+  write_position: AtomicUsize,
+  read_position: AtomicUsize,
+  overflow_count: AtomicU32,
+}
+
+impl EventQueue {
+  pub fn new(capacity: usize) -> EventQueue {
+    EventQueue {
+      slots: (0..capacity).map(|_| UnsafeCell::new(None)).collect(),
+      write_position: AtomicUsize::new(0),
+      read_position: AtomicUsize::new(0),
+      overflow_count: AtomicU32::new(0),
+    }
+  }
+
+  pub fn push(&self, event: QueuedEvent) {
+    let write_position = self.write_position.load(Ordering::Relaxed);
+    let read_position = self.read_position.load(Ordering::Acquire);
+
+    if write_position.wrapping_sub(read_position) >= self.slots.len() {
+      self.overflow_count.fetch_add(1, Ordering::Relaxed);
+      return;
+    }
+
+    unsafe {
+      *self.slots[write_position % self.slots.len()].get() = Some(event);
+    }
+
+    self
+      .write_position
+      .store(write_position.wrapping_add(1), Ordering::Release);
+  }
+
+  pub fn pop(&self) -> Option<QueuedEvent> {
+    let read_position = self.read_position.load(Ordering::Relaxed);
+    let write_position = self.write_position.load(Ordering::Acquire);
+
+    if read_position == write_position {
+      return None;
+    }
+
+    let event = unsafe { (*self.slots[read_position % self.slots.len()].get()).take() };
+
+    self
+      .read_position
+      .store(read_position.wrapping_add(1), Ordering::Release);
+
+    event
+  }
+
+  pub fn overflow_count_ptr(&self) -> usize {
+    self.overflow_count.as_ptr() as usize
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn event(quantum: u64) -> QueuedEvent {
+    QueuedEvent {
+      quantum,
+      sample_offset: 0,
+      id: 0,
+      event: ModuleEvent::SamplerAllocateSuccess { ptr: 0 },
+    }
+  }
+
+  fn overflow_count(queue: &EventQueue) -> u32 {
+    queue.overflow_count.load(Ordering::Relaxed)
+  }
+
+  #[test]
+  fn pops_in_order() {
+    let queue = EventQueue::new(4);
+    for quantum in 0..3 {
+      queue.push(event(quantum));
+    }
+
+    for quantum in 0..3 {
+      assert_eq!(queue.pop().unwrap().quantum, quantum);
+    }
+    assert!(queue.pop().is_none());
+  }
+
+  #[test]
+  fn drops_and_counts_events_when_full() {
+    let queue = EventQueue::new(2);
+    for quantum in 0..5 {
+      queue.push(event(quantum));
+    }
+
+    assert_eq!(overflow_count(&queue), 3);
+    assert_eq!(queue.pop().unwrap().quantum, 0);
+    assert_eq!(queue.pop().unwrap().quantum, 1);
+    assert!(queue.pop().is_none());
+  }
+
+  #[test]
+  fn reuses_slots_after_popping() {
+    let queue = EventQueue::new(2);
+    for quantum in 0..10 {
+      queue.push(event(quantum));
+      assert_eq!(queue.pop().unwrap().quantum, quantum);
+    }
+
+    assert_eq!(overflow_count(&queue), 0);
+    assert!(queue.pop().is_none());
+  }
+}
@@ -19,6 +19,11 @@ impl AllpassFilter {
     self.gain = gain;
   }
 
+  pub fn reset(&mut self) {
+    self.feedback_delay.reset();
+    self.input_delay.reset();
+  }
+
   pub fn step(&mut self, input: f32) -> f32 {
     let output =
       input * self.gain + self.input_delay.read() - self.gain * self.feedback_delay.read();
@@ -18,6 +18,8 @@ fn voltage_to_freq(voltage: f32) -> f32 {
   f32::min(13.75 * f32::powf(2.0, voltage + 5.0), SAMPLE_RATE_F32 / 2.0)
 }
 
+// Parameters which aren't numbers make the output of these non-finite, which the module guard
+// silences, instead of aborting the audio thread.
 fn get_q_params(freq: f32, q: f32) -> (f32, f32) {
   let omega = 2.0 * std::f32::consts::PI * freq * INV_SAMPLE_RATE;
   let (sin_omega, cos_omega) = f32::sin_cos(omega);
@@ -36,9 +38,6 @@ fn get_slope_params(freq: f32, db_gain: f32, slope: f32) -> (f32, f32, f32) {
       (amp + 1.0 / amp) * (1.0 / f32::max(f32::EPSILON, slope) - 1.0) + 2.0,
     ));
 
-  assert!(!alpha.is_nan());
-  assert!(!alpha.is_infinite());
-
   (alpha, cos_omega, amp)
 }
 
@@ -160,6 +159,21 @@ impl BiquadFilter {
     self.a2 = amp_inc - amp_dec * cos_omega - double_sqrt_amp_alpha;
   }
 
+  pub fn set_coefficients(&mut self, b: [f32; 3], a: [f32; 3]) {
+    self.b0 = b[0];
+    self.b1 = b[1];
+    self.b2 = b[2];
+
+    self.a0 = a[0];
+    self.a1 = a[1];
+    self.a2 = a[2];
+  }
+
+  pub fn reset(&mut self) {
+    self.input_buffer = [0.0; 2];
+    self.feedback_buffer = [0.0; 2];
+  }
+
   pub fn get_coefficients(&self) -> Vec<f32> {
     vec![self.a0, self.a1, self.a2, self.b0, self.b1, self.b2]
   }
@@ -2,35 +2,54 @@
 
 use audio_buffer::AudioBuffer;
 use core::arch::wasm32::memory_atomic_wait64;
+use event_queue::{EventQueue, QueuedEvent};
 use filters::biquad_filter::BiquadFilter;
 use lazy_static::lazy_static;
+use master_bus::MasterBus;
+use master_meter::MasterMeter;
+use module_guard::ModuleGuard;
 use modules::adsr::ADSR;
+use modules::arpeggiator::Arpeggiator;
 use modules::audio_out::AudioOut;
 use modules::bouncy_boi::BouncyBoi;
+use modules::chaos::Chaos;
 use modules::chorus::Chorus;
 use modules::clock::Clock;
 use modules::delay::Delay;
 use modules::eq3::EQ3;
+use modules::euclidean::Euclidean;
 use modules::fdn_reverb::FDNReverb;
+use modules::fm_voice::FMVoice;
 use modules::gain::Gain;
+use modules::gate_sequencer::GateSequencer;
+use modules::karplus_strong::KarplusStrong;
 use modules::lfo::LFO;
 use modules::limiter::Limiter;
 use modules::midi::MIDI;
 use modules::mixer::Mixer;
+use modules::modal_resonator::ModalResonator;
+use modules::noise::Noise;
 use modules::oscillator::Oscillator;
 use modules::oscilloscope::Oscilloscope;
 use modules::piano_roll::PianoRoll;
 use modules::pow_shaper::PowShaper;
+use modules::quantizer::Quantizer;
 use modules::ring_mod::RingMod;
+use modules::sample_and_hold::SampleAndHold;
 use modules::sampler::Sampler;
 use modules::sequencer::Sequencer;
 use modules::sideq::Sideq;
+use modules::turing_machine::TuringMachine;
 use modules::virtual_controller::VirtualController;
+use modules::wavetable::{Wavetable, WavetableMipmap};
+use recorder::{Recorder, RecorderExchange};
 use rw_lock::RwLock;
 use serde::Serialize;
+use std::cell::Cell;
 use std::collections::{HashMap, HashSet};
 use std::ops::{Index, IndexMut};
-use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use tuning::Tuning;
 use wasm_bindgen::prelude::*;
 
 pub mod adsr_curve;
@@ -41,12 +60,20 @@ pub mod audio_param;
 pub mod barrier;
 pub mod delay_line;
 pub mod edge_detector;
+pub mod event_queue;
 pub mod filters;
+pub mod master_bus;
+pub mod master_meter;
 pub mod modulate_core;
 pub mod module;
+pub mod module_guard;
 pub mod modules;
+pub mod recorder;
 pub mod ring_buffer;
+pub mod rng;
 pub mod rw_lock;
+pub mod tuning;
+pub mod ui_state;
 pub mod util;
 pub mod vec;
 pub mod windowed_sinc;
@@ -70,8 +97,13 @@ struct ModuleConnection {
 
 struct ModuleStore {
   modules: Vec<Box<dyn module::Module>>,
+  guards: Vec<ModuleGuard>,
+  // Id of the module at each index.
+  ids: Vec<module::ModuleId>,
   module_ids: HashMap<module::ModuleId, usize>,
   rw_lock: RwLock,
+  // Events of the current quantum, merged across modules before they are queued.
+  merged_events: Vec<QueuedEvent>,
 }
 
 impl Index<usize> for ModuleStore {
@@ -91,8 +123,11 @@ impl ModuleStore {
   pub fn new() -> ModuleStore {
     ModuleStore {
       modules: vec![],
+      guards: vec![],
+      ids: vec![],
       module_ids: HashMap::new(),
       rw_lock: RwLock::new(),
+      merged_events: Vec::with_capacity(EVENT_QUEUE_CAPACITY),
     }
   }
 
@@ -103,12 +138,16 @@ impl ModuleStore {
   pub fn insert(&mut self, id: module::ModuleId, module: Box<dyn module::Module>) {
     let index = self.modules.len();
     self.modules.push(module);
+    self.guards.push(ModuleGuard::default());
+    self.ids.push(id);
     self.module_ids.insert(id, index);
   }
 
   pub fn remove(&mut self, id: &module::ModuleId) {
     let module_index = *self.module_ids.get(&id).unwrap();
     self.modules.remove(module_index);
+    self.guards.remove(module_index);
+    self.ids.remove(module_index);
     self.module_ids.remove(&id);
 
     for (_, i) in self.module_ids.iter_mut() {
@@ -128,6 +167,73 @@ impl ModuleStore {
     let module_index = *self.module_ids.get(id).unwrap();
     self.modules.get_mut(module_index)
   }
+
+  // Moves the pending events of every module into the queue. Each module pops its events in the
+  // order they happened, and these are merged by their sample offset so that the whole quantum is
+  // queued in order, with events at the same sample in the order of the modules.
+  pub fn queue_events(&mut self, events: &EventQueue, quantum: u64) {
+    let mut merged_events = std::mem::take(&mut self.merged_events);
+
+    for module_index in 0..self.modules.len() {
+      self.pop_module_events(module_index, quantum, |event| {
+        // More events than fit in the queue are about to overflow it, so their order no longer
+        // matters.
+        if merged_events.len() == merged_events.capacity() {
+          events.push(event);
+          return;
+        }
+
+        let index = merged_events
+          .iter()
+          .rposition(|merged| merged.sample_offset <= event.sample_offset)
+          .map_or(0, |index| index + 1);
+        merged_events.insert(index, event);
+      });
+    }
+
+    for event in merged_events.drain(..) {
+      events.push(event);
+    }
+
+    self.merged_events = merged_events;
+  }
+
+  pub fn queue_module_events(&mut self, module_index: usize, events: &EventQueue, quantum: u64) {
+    self.pop_module_events(module_index, quantum, |event| events.push(event));
+  }
+
+  fn pop_module_events(
+    &mut self,
+    module_index: usize,
+    quantum: u64,
+    mut push: impl FnMut(QueuedEvent),
+  ) {
+    let id = self.ids[module_index];
+
+    // Faults are detected after the module has processed the whole quantum.
+    if let Some(event) = self.guards[module_index].pop_event() {
+      push(QueuedEvent {
+        quantum,
+        sample_offset: modulate_core::QUANTUM_SIZE - 1,
+        id,
+        event,
+      });
+    }
+
+    // A module which has panicked is left alone.
+    if self.guards[module_index].is_faulted() {
+      return;
+    }
+
+    while let Some((sample_offset, event)) = self.modules[module_index].pop_event() {
+      push(QueuedEvent {
+        quantum,
+        sample_offset,
+        id,
+        event,
+      });
+    }
+  }
 }
 
 #[derive(Serialize)]
@@ -137,6 +243,10 @@ struct ContextPointers {
   worker_performance: usize,
   worker_position: usize,
   audio_worklet_position: usize,
+  master_meter: usize,
+  master_bus_gain_reduction: usize,
+  recorded_frames: usize,
+  event_overflow_count: usize,
 }
 
 struct WorkerContext {
@@ -144,6 +254,8 @@ struct WorkerContext {
 
   barrier: barrier::Barrier,
   current_module: AtomicUsize,
+  // Workers which haven't given up after a panic.
+  live_workers: AtomicUsize,
 
   // Output buffer positions
   audio_worklet_position: AtomicU64,
@@ -155,8 +267,29 @@ struct WorkerContext {
   audio_outputs: HashSet<module::ModuleId>,
   output_buffers_left: [AudioBuffer; NUM_OUTPUT_BUFFERS],
   output_buffers_right: [AudioBuffer; NUM_OUTPUT_BUFFERS],
+  master_bus: MasterBus,
+  master_meter: MasterMeter,
+  recorder: Recorder,
+  tuning: Tuning,
+  events: EventQueue,
 
   performance: Vec<f32>,
+
+  // Whether subnormal values in module outputs should be flushed to zero after processing.
+  flush_denormals: AtomicBool,
+}
+
+// Where in the processing of a quantum a worker is, so that a worker which panics knows which
+// locks and barriers it has to let go of.
+#[derive(Clone, Copy)]
+enum WorkerStage {
+  Idle,
+  // Holding a read lock, as the leader of the barrier which starts the quantum.
+  Swapping,
+  // Holding a read lock between the two barriers of the quantum.
+  Processing,
+  // Holding a read lock, as the leader of the barrier which writes the output buffers.
+  Writing,
 }
 
 struct Worker {
@@ -164,12 +297,43 @@ struct Worker {
   performance_samples: [f32; 64],
   modules: *mut ModuleStore,
   context: *mut WorkerContext,
+
+  stage: WorkerStage,
+  // Index of the module currently being processed by this worker, which is faulted if it panics.
+  processing_module: Option<usize>,
+  // Set once the worker has panicked. It then stays in the barriers but leaves the modules to the
+  // other workers.
+  given_up: bool,
+}
+
+thread_local! {
+  // The worker running on this thread, for the panic hook.
+  static CURRENT_WORKER: Cell<*mut Worker> = const { Cell::new(std::ptr::null_mut()) };
+}
+
+// Panics abort the whole WebAssembly call, which can't be resumed from without leaking the stack
+// and leaving the locks and barriers in whatever state they were in, deadlocking the other workers.
+// A worker which panics therefore doesn't return from the panic hook, and carries on from there
+// instead, after letting go of what it held.
+fn panic_hook(info: &std::panic::PanicHookInfo) {
+  console_error_panic_hook::hook(info);
+
+  let worker = CURRENT_WORKER.with(|worker| worker.get());
+  if let Some(worker) = unsafe { worker.as_mut() } {
+    worker.give_up();
+  }
 }
 
 const NUM_OUTPUT_BUFFERS: usize = 16;
+const EVENT_QUEUE_CAPACITY: usize = 1024;
 
 impl Worker {
   fn run(&mut self) {
+    CURRENT_WORKER.with(|worker| worker.set(self));
+    self.run_loop();
+  }
+
+  fn run_loop(&mut self) -> ! {
     let (context, modules) = unsafe {
       (
         self.context.as_mut().unwrap(),
@@ -205,45 +369,129 @@ impl Worker {
       let start_time = performance.now();
 
       modules.rw_lock.lock_read();
+      self.stage = WorkerStage::Processing;
 
       // Have the leader swap the buffers.
       context.barrier.wait_and_do(|| {
+        self.stage = WorkerStage::Swapping;
         modules.swap_buffers();
         context.current_module.store(0, Ordering::SeqCst);
+        self.stage = WorkerStage::Processing;
       });
 
-      loop {
-        let module_index = context.current_module.fetch_add(1, Ordering::SeqCst);
-        if module_index >= modules.len() {
-          break;
-        }
+      if !self.given_up {
+        self.process_modules(context, modules);
+      }
+      self.stage = WorkerStage::Idle;
+      modules.rw_lock.unlock_read();
 
-        let module = &mut modules[module_index];
-        for parameter in module.get_parameters() {
-          parameter.process(context.worker_position);
-        }
+      self.write_output_buffers(context, modules);
+
+      let current_pos = context.worker_position as usize % 64;
+      let last_pos = (context.worker_position + 1) as usize % 64;
+      self.performance_samples[current_pos] = (performance.now() - start_time) as f32;
+
+      context.performance[self.id] += self.performance_samples[current_pos] / 64.0;
+      context.performance[self.id] -= self.performance_samples[last_pos] / 64.0;
+    }
+  }
+
+  // Called from the panic hook. Faults the module which panicked, finishes the quantum like the
+  // other workers expect and keeps on taking part in the barriers without processing modules. A
+  // second panic on the worker aborts, as the hook doesn't run for panics inside of it.
+  fn give_up(&mut self) -> ! {
+    let (context, modules) = unsafe {
+      (
+        self.context.as_mut().unwrap(),
+        self.modules.as_mut().unwrap(),
+      )
+    };
 
-        module.process(context.worker_position);
+    if !self.given_up {
+      self.given_up = true;
+      context.live_workers.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    if let Some(module_index) = self.processing_module.take() {
+      modules.guards[module_index].fault(&mut modules.modules[module_index]);
+    }
+
+    match self.stage {
+      WorkerStage::Idle => {}
+      WorkerStage::Swapping => {
+        context.barrier.release();
+        self.stage = WorkerStage::Idle;
+        modules.rw_lock.unlock_read();
+        self.write_output_buffers(context, modules);
+      }
+      WorkerStage::Processing => {
+        self.stage = WorkerStage::Idle;
+        modules.rw_lock.unlock_read();
+        self.write_output_buffers(context, modules);
       }
+      WorkerStage::Writing => {
+        self.stage = WorkerStage::Idle;
+        modules.rw_lock.unlock_read();
+        context.barrier.release();
+      }
+    }
 
-      modules.rw_lock.unlock_read();
+    self.run_loop()
+  }
 
-      // Have the leader write the output buffers
-      context.barrier.wait_and_do(|| {
-        modules.rw_lock.lock_read();
-
-        // NOTE: If `worker_position` changes are not done by the barrier leader, it must be converted
-        // into an atomic. Currently only a single thread reads and writes to it.
-        context.worker_position += 1;
-        let output_index = context.worker_position % NUM_OUTPUT_BUFFERS as u64;
-        let output_buf_l = &mut context.output_buffers_left[output_index as usize];
-        let output_buf_r = &mut context.output_buffers_right[output_index as usize];
-
-        for sample in 0..modulate_core::QUANTUM_SIZE {
-          (*output_buf_l)[sample] = 0.0;
-          (*output_buf_r)[sample] = 0.0;
-        }
+  fn process_modules(&mut self, context: &mut WorkerContext, modules: &mut ModuleStore) {
+    loop {
+      let module_index = context.current_module.fetch_add(1, Ordering::SeqCst);
+      if module_index >= modules.len() {
+        break;
+      }
+
+      let module = &mut modules.modules[module_index];
+      let guard = &mut modules.guards[module_index];
+
+      if guard.is_faulted() {
+        guard.silence(module);
+        continue;
+      }
+
+      // The writes must not be elided, as the value is only read by the panic hook.
+      unsafe { std::ptr::write_volatile(&mut self.processing_module, Some(module_index)) };
+
+      for parameter in module.get_parameters() {
+        parameter.process(context.worker_position);
+      }
+
+      module.process(context.worker_position);
+
+      unsafe { std::ptr::write_volatile(&mut self.processing_module, None) };
+
+      guard.inspect(module, context.flush_denormals.load(Ordering::Relaxed));
+    }
+  }
 
+  fn write_output_buffers(&mut self, context: &mut WorkerContext, modules: &mut ModuleStore) {
+    // Have the leader write the output buffers
+    context.barrier.wait_and_do(|| {
+      modules.rw_lock.lock_read();
+      self.stage = WorkerStage::Writing;
+
+      modules.queue_events(&context.events, context.worker_position);
+
+      // NOTE: If `worker_position` changes are not done by the barrier leader, it must be converted
+      // into an atomic. Currently only a single thread reads and writes to it.
+      context.worker_position += 1;
+      let output_index = context.worker_position % NUM_OUTPUT_BUFFERS as u64;
+      let output_buf_l = &mut context.output_buffers_left[output_index as usize];
+      let output_buf_r = &mut context.output_buffers_right[output_index as usize];
+
+      for sample in 0..modulate_core::QUANTUM_SIZE {
+        (*output_buf_l)[sample] = 0.0;
+        (*output_buf_r)[sample] = 0.0;
+      }
+
+      // Without any workers left to process the modules their outputs go stale, so only silence
+      // is written then.
+      if context.live_workers.load(Ordering::SeqCst) > 0 {
         for audio_output in context.audio_outputs.iter() {
           let module = modules.get_mut(audio_output).unwrap();
           let outputs = module.get_outputs();
@@ -255,17 +503,15 @@ impl Worker {
             (*output_buf_r)[sample] += output_r[sample];
           }
         }
+      }
 
-        modules.rw_lock.unlock_read();
-      });
-
-      let current_pos = context.worker_position as usize % 64;
-      let last_pos = (context.worker_position + 1) as usize % 64;
-      self.performance_samples[current_pos] = (performance.now() - start_time) as f32;
+      context.master_bus.process(output_buf_l, output_buf_r);
+      context.master_meter.process(output_buf_l, output_buf_r);
+      context.recorder.process(output_buf_l, output_buf_r);
 
-      context.performance[self.id] += self.performance_samples[current_pos] / 64.0;
-      context.performance[self.id] -= self.performance_samples[last_pos] / 64.0;
-    }
+      self.stage = WorkerStage::Idle;
+      modules.rw_lock.unlock_read();
+    });
   }
 }
 
@@ -277,32 +523,46 @@ lazy_static! {
     > = HashMap::new();
 
     module_map.insert("ADSR", |_| ADSR::new());
+    module_map.insert("Arpeggiator", |ctx| Arpeggiator::new(&ctx.tuning));
     module_map.insert("AudioOut", |_| AudioOut::new());
     module_map.insert("BiquadFilter", |_| {
       modules::biquad_filter::BiquadFilter::new()
     });
     module_map.insert("BouncyBoi", |_| BouncyBoi::new());
+    module_map.insert("Chaos", |_| Chaos::new());
     module_map.insert("Chorus", |_| Chorus::new());
     module_map.insert("Clock", |_| Clock::new());
     module_map.insert("Delay", |_| Delay::new());
     module_map.insert("EQ3", |_| EQ3::new());
+    module_map.insert("Euclidean", |_| Euclidean::new());
     module_map.insert("FDNReverb", |_| FDNReverb::new());
+    module_map.insert("FMVoice", |_| FMVoice::new());
     module_map.insert("Gain", |_| Gain::new());
+    module_map.insert("GateSequencer", |_| GateSequencer::new());
+    module_map.insert("KarplusStrong", |_| KarplusStrong::new());
     module_map.insert("LFO", |_| LFO::new());
     module_map.insert("Limiter", |_| Limiter::new());
-    module_map.insert("MIDI", |_| MIDI::new());
+    module_map.insert("MIDI", |ctx| MIDI::new(&ctx.tuning));
     module_map.insert("Mixer", |_| Mixer::new());
+    module_map.insert("ModalResonator", |_| ModalResonator::new());
+    module_map.insert("Noise", |_| Noise::new());
     module_map.insert("Oscillator", |_| Oscillator::new());
     module_map.insert("Oscilloscope", |ctx| {
       Oscilloscope::new(ctx.worker_position as usize)
     });
     module_map.insert("PianoRoll", |_| PianoRoll::new());
     module_map.insert("PowShaper", |_| PowShaper::new());
+    module_map.insert("Quantizer", |ctx| Quantizer::new(&ctx.tuning));
     module_map.insert("RingMod", |_| RingMod::new());
+    module_map.insert("SampleAndHold", |_| SampleAndHold::new());
     module_map.insert("Sampler", |_| Sampler::new());
-    module_map.insert("Sequencer", |_| Sequencer::new());
+    module_map.insert("Sequencer", |ctx| Sequencer::new(&ctx.tuning));
     module_map.insert("Sideq", |_| Sideq::new());
-    module_map.insert("VirtualController", |_| VirtualController::new());
+    module_map.insert("TuringMachine", |_| TuringMachine::new());
+    module_map.insert("VirtualController", |ctx| {
+      VirtualController::new(&ctx.tuning)
+    });
+    module_map.insert("Wavetable", |_| Wavetable::new());
 
     module_map
   };
@@ -315,6 +575,12 @@ struct ModulateEngine {
 
   workers: Vec<Worker>,
   worker_context: WorkerContext,
+
+  // Chunks of the current take which have been collected from the recorder.
+  recording: Vec<Vec<f32>>,
+  recording_truncated: bool,
+  // Kept for the next collection while the recorder still has a spare chunk.
+  recording_spare_chunk: Option<Vec<f32>>,
 }
 
 impl ModulateEngine {
@@ -329,6 +595,7 @@ impl ModulateEngine {
         num_threads,
         barrier: barrier::Barrier::new(num_threads),
         current_module: AtomicUsize::new(0),
+        live_workers: AtomicUsize::new(num_threads),
 
         worker_position: 0,
         audio_worklet_position: AtomicU64::new(0),
@@ -336,9 +603,20 @@ impl ModulateEngine {
         audio_outputs: HashSet::new(),
         output_buffers_left: [AudioBuffer::default(); NUM_OUTPUT_BUFFERS],
         output_buffers_right: [AudioBuffer::default(); NUM_OUTPUT_BUFFERS],
+        master_bus: MasterBus::new(),
+        master_meter: MasterMeter::new(),
+        recorder: Recorder::new(),
+        tuning: Tuning::default(),
+        events: EventQueue::new(EVENT_QUEUE_CAPACITY),
 
         performance: vec![0.0; num_threads],
+
+        flush_denormals: AtomicBool::new(true),
       },
+
+      recording: vec![],
+      recording_truncated: false,
+      recording_spare_chunk: None,
     }
   }
 
@@ -355,6 +633,9 @@ impl ModulateEngine {
         performance_samples: [0.0; 64],
         modules: &mut self.modules,
         context: &mut self.worker_context,
+        stage: WorkerStage::Idle,
+        processing_module: None,
+        given_up: false,
       };
 
       self.workers.push(worker);
@@ -374,6 +655,10 @@ impl ModulateEngine {
       worker_performance: self.worker_context.performance.as_ptr() as usize,
       worker_position: &self.worker_context.worker_position as *const u64 as usize,
       audio_worklet_position: self.worker_context.audio_worklet_position.as_ptr() as usize,
+      master_meter: self.worker_context.master_meter.readings_ptr(),
+      master_bus_gain_reduction: self.worker_context.master_bus.gain_reduction_ptr(),
+      recorded_frames: self.worker_context.recorder.recorded_frames_ptr(),
+      event_overflow_count: self.worker_context.events.overflow_count_ptr(),
     }
   }
 
@@ -461,6 +746,7 @@ impl ModulateEngine {
         .modules
         .get_mut(&from_module_id)
         .expect("connect_to_input: from_module_id doesn't exist");
+      from_module.connect_output(from_output);
       from_module.get_output_buffer_ptr(from_output)
     };
 
@@ -502,6 +788,7 @@ impl ModulateEngine {
         .modules
         .get_mut(&from_module_id)
         .expect("connect_to_parameter: from_module_id doesn't exist");
+      from_module.connect_output(from_output);
       from_module.get_output_buffer_ptr(from_output)
     };
 
@@ -522,6 +809,11 @@ impl ModulateEngine {
     self.modules.rw_lock.lock_write();
 
     if let Some(connection) = self.connections.get(&connection_id) {
+      let (from_module_id, from_output) = connection.from;
+      if let Some(from_module) = self.modules.get_mut(&from_module_id) {
+        from_module.disconnect_output(from_output);
+      }
+
       match connection.to {
         ConnectionTarget::Input(to_module_id, to_input) => {
           if let Some(to_module) = self.modules.get_mut(&to_module_id) {
@@ -552,7 +844,39 @@ impl ModulateEngine {
       Err(err) => panic!("error deserializing message: {}", err.to_string().as_str()),
     }
 
+    // Events in response to messages are queued right away, as the audio thread might not be
+    // running.
+    let module_index = self.modules.module_ids[&module_id];
+    self.modules.queue_module_events(
+      module_index,
+      &self.worker_context.events,
+      self.worker_context.worker_position,
+    );
+
+    self.modules.rw_lock.unlock_write();
+  }
+
+  // The mipmap is built before locking the modules and only exchanged with the previous one under
+  // the lock, so that loading a large wavetable doesn't stall the workers.
+  pub fn load_wavetable(
+    &mut self,
+    module_id: module::ModuleId,
+    samples: &[f32],
+    length: usize,
+    frames: usize,
+  ) {
+    let mut mipmap = Some(Box::new(WavetableMipmap::build(samples, length, frames)));
+
+    self.modules.rw_lock.lock_write();
+
+    if let Some(module) = self.modules.get_mut(&module_id) {
+      module.swap_wavetable(&mut mipmap);
+    }
+
     self.modules.rw_lock.unlock_write();
+
+    // Now holds the previous mipmap of the module.
+    drop(mipmap);
   }
 
   pub fn get_module_pointers(&mut self, module_id: module::ModuleId) -> Vec<usize> {
@@ -567,27 +891,140 @@ impl ModulateEngine {
     pointers
   }
 
-  pub fn collect_module_events(&mut self) -> Vec<module::ModuleEventWithId> {
-    // NOTE: This need not be atomic or `lock_write`ed, as the only place where other modifying
-    // operations can be called is this thread (main worker)
+  pub fn clear_module_fault(&mut self, module_id: module::ModuleId) {
+    self.modules.rw_lock.lock_write();
+
+    let module_index = self.modules.module_ids[&module_id];
+    self.modules.guards[module_index].clear(&mut self.modules.modules[module_index]);
+
+    self.modules.rw_lock.unlock_write();
+  }
+
+  pub fn collect_module_events(&mut self) -> Vec<QueuedEvent> {
+    // NOTE: The queue is only popped from this thread (main worker), so this need not be
+    // `lock_write`ed.
     let mut events = vec![];
-    let module_ids = &self.modules.module_ids;
-    let modules = &mut self.modules.modules;
-
-    for (&id, &idx) in module_ids.iter() {
-      let module = &mut modules[idx];
-      loop {
-        match module.pop_event() {
-          Some(event) => {
-            events.push(module::ModuleEventWithId { id, event });
-          }
-          None => break,
-        };
-      }
+
+    while let Some(event) = self.worker_context.events.pop() {
+      events.push(event);
     }
 
     events
   }
+
+  pub fn set_flush_denormals(&mut self, flush_denormals: bool) {
+    self
+      .worker_context
+      .flush_denormals
+      .store(flush_denormals, Ordering::Relaxed);
+  }
+
+  pub fn set_master_bus(&mut self, enabled: bool, ceiling: f32) {
+    self.modules.rw_lock.lock_write();
+    self.worker_context.master_bus.set_enabled(enabled, ceiling);
+    self.modules.rw_lock.unlock_write();
+  }
+
+  pub fn set_reference_frequency(&mut self, freq: f32) -> Result<(), String> {
+    self.modules.rw_lock.lock_write();
+    let result = tuning::set_reference_frequency(freq);
+    self.worker_context.tuning.update();
+    self.modules.rw_lock.unlock_write();
+
+    result
+  }
+
+  // Without a scale the tuning goes back to 12 tone equal temperament. A keyboard mapping is only
+  // used together with a scale.
+  pub fn set_tuning(&mut self, scl: Option<String>, kbm: Option<String>) -> Result<(), String> {
+    let scale = scl.as_deref().map(tuning::parse_scl).transpose()?;
+    let mapping = kbm.as_deref().map(tuning::parse_kbm).transpose()?;
+
+    self.modules.rw_lock.lock_write();
+    self.worker_context.tuning.set_scale(scale, mapping);
+    self.modules.rw_lock.unlock_write();
+
+    Ok(())
+  }
+
+  pub fn reset_integrated_loudness(&mut self) {
+    self.modules.rw_lock.lock_write();
+    self.worker_context.master_meter.reset_integrated_loudness();
+    self.modules.rw_lock.unlock_write();
+  }
+
+  pub fn arm_recording(&mut self, pre_roll_seconds: f32) -> Result<(), String> {
+    let mut pre_roll = Recorder::allocate_pre_roll(pre_roll_seconds);
+
+    self.modules.rw_lock.lock_write();
+    let result = self.worker_context.recorder.arm(&mut pre_roll);
+    self.modules.rw_lock.unlock_write();
+
+    // Now holds the previous pre-roll, or the new one if arming failed.
+    drop(pre_roll);
+    result
+  }
+
+  pub fn start_recording(&mut self) {
+    let mut exchange = RecorderExchange::new(Some(Recorder::allocate_chunk()));
+
+    self.modules.rw_lock.lock_write();
+    self.worker_context.recorder.start(&mut exchange);
+    self.modules.rw_lock.unlock_write();
+
+    // Frees what was left of the previous take.
+    drop(exchange);
+    self.recording.clear();
+    self.recording_truncated = false;
+
+    self.collect_recording();
+  }
+
+  // Has to be called regularly while recording, as the recorder can only hold a few chunks
+  // before the take is cut short.
+  pub fn collect_recording(&mut self) {
+    let spare_chunk = self
+      .recording_spare_chunk
+      .take()
+      .unwrap_or_else(Recorder::allocate_chunk);
+    let mut exchange = RecorderExchange::new(Some(spare_chunk));
+
+    self.modules.rw_lock.lock_write();
+    self.worker_context.recorder.exchange(&mut exchange);
+    self.modules.rw_lock.unlock_write();
+
+    self.recording.append(&mut exchange.filled);
+    self.recording_truncated |= exchange.truncated;
+    self.recording_spare_chunk = exchange.spare;
+  }
+
+  pub fn stop_recording(&mut self) {
+    self.modules.rw_lock.lock_write();
+    self.worker_context.recorder.stop();
+    self.modules.rw_lock.unlock_write();
+  }
+
+  pub fn export_recording(&mut self) -> Vec<u8> {
+    let mut exchange = RecorderExchange::new(None);
+
+    self.modules.rw_lock.lock_write();
+    self.worker_context.recorder.finish(&mut exchange);
+    self.modules.rw_lock.unlock_write();
+
+    self.recording.append(&mut exchange.filled);
+    self.recording_truncated |= exchange.truncated;
+
+    let wav = Recorder::encode_wav(&self.recording);
+    self.recording = vec![];
+    self.recording_spare_chunk = None;
+    wav
+  }
+
+  // Whether the last take was cut short, either because it got too long or because it wasn't
+  // collected in time.
+  pub fn is_recording_truncated(&self) -> bool {
+    self.recording_truncated
+  }
 }
 
 #[wasm_bindgen]
@@ -599,7 +1036,7 @@ pub struct ModulateEngineWrapper {
 impl ModulateEngineWrapper {
   #[wasm_bindgen(constructor)]
   pub fn new(num_threads: usize) -> ModulateEngineWrapper {
-    std::panic::set_hook(Box::new(console_error_panic_hook::hook));
+    std::panic::set_hook(Box::new(panic_hook));
 
     ModulateEngineWrapper {
       engine: ModulateEngine::new(num_threads),
@@ -707,15 +1144,97 @@ impl ModulateEngineWrapper {
     self.engine.send_message_to_module(module_id, message);
   }
 
+  #[wasm_bindgen(js_name = loadWavetable)]
+  pub fn load_wavetable(
+    &mut self,
+    module_id: module::ModuleId,
+    samples: &[f32],
+    length: usize,
+    frames: usize,
+  ) {
+    self
+      .engine
+      .load_wavetable(module_id, samples, length, frames);
+  }
+
   #[wasm_bindgen(js_name = getModulePointers)]
   pub fn get_module_pointers(&mut self, module_id: module::ModuleId) -> Vec<usize> {
     self.engine.get_module_pointers(module_id)
   }
 
+  #[wasm_bindgen(js_name = clearModuleFault)]
+  pub fn clear_module_fault(&mut self, module_id: module::ModuleId) {
+    self.engine.clear_module_fault(module_id);
+  }
+
   #[wasm_bindgen(js_name = collectModuleEvents)]
   pub fn collect_module_events(&mut self) -> JsValue {
     serde_wasm_bindgen::to_value(&self.engine.collect_module_events()).unwrap()
   }
+
+  #[wasm_bindgen(js_name = setFlushDenormals)]
+  pub fn set_flush_denormals(&mut self, flush_denormals: bool) {
+    self.engine.set_flush_denormals(flush_denormals);
+  }
+
+  #[wasm_bindgen(js_name = setMasterBus)]
+  pub fn set_master_bus(&mut self, enabled: bool, ceiling: f32) {
+    self.engine.set_master_bus(enabled, ceiling);
+  }
+
+  #[wasm_bindgen(js_name = setReferenceFrequency)]
+  pub fn set_reference_frequency(&mut self, freq: f32) -> Result<(), JsValue> {
+    self
+      .engine
+      .set_reference_frequency(freq)
+      .map_err(|err| JsValue::from_str(&err))
+  }
+
+  #[wasm_bindgen(js_name = setTuning)]
+  pub fn set_tuning(&mut self, scl: Option<String>, kbm: Option<String>) -> Result<(), JsValue> {
+    self
+      .engine
+      .set_tuning(scl, kbm)
+      .map_err(|err| JsValue::from_str(&err))
+  }
+
+  #[wasm_bindgen(js_name = resetIntegratedLoudness)]
+  pub fn reset_integrated_loudness(&mut self) {
+    self.engine.reset_integrated_loudness();
+  }
+
+  #[wasm_bindgen(js_name = armRecording)]
+  pub fn arm_recording(&mut self, pre_roll_seconds: f32) -> Result<(), JsValue> {
+    self
+      .engine
+      .arm_recording(pre_roll_seconds)
+      .map_err(|err| JsValue::from_str(&err))
+  }
+
+  #[wasm_bindgen(js_name = startRecording)]
+  pub fn start_recording(&mut self) {
+    self.engine.start_recording();
+  }
+
+  #[wasm_bindgen(js_name = collectRecording)]
+  pub fn collect_recording(&mut self) {
+    self.engine.collect_recording();
+  }
+
+  #[wasm_bindgen(js_name = stopRecording)]
+  pub fn stop_recording(&mut self) {
+    self.engine.stop_recording();
+  }
+
+  #[wasm_bindgen(js_name = exportRecording)]
+  pub fn export_recording(&mut self) -> Vec<u8> {
+    self.engine.export_recording()
+  }
+
+  #[wasm_bindgen(js_name = isRecordingTruncated)]
+  pub fn is_recording_truncated(&self) -> bool {
+    self.engine.is_recording_truncated()
+  }
 }
 
 #[wasm_bindgen(js_name = workerEntry)]
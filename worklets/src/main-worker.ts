@@ -9,6 +9,16 @@ import { initSync, ModulateEngineWrapper } from '../pkg/modulate'
 import { Module } from './modules'
 
 let engine: ModulateEngineWrapper | null = null
+
+// While recording, the engine has to be handed empty chunks to record into and
+// have the full ones taken off it, or the take is cut short.
+let collectRecordingInterval: ReturnType<typeof setInterval> | null = null
+const stopCollectingRecording = () => {
+  if (collectRecordingInterval !== null) {
+    clearInterval(collectRecordingInterval)
+    collectRecordingInterval = null
+  }
+}
 const requestHandlers: {
   [K in EngineMessageType]: (
     req: EngineRequest<K>
@@ -27,6 +37,10 @@ const requestHandlers: {
       worker_performance,
       worker_position,
       audio_worklet_position,
+      master_meter,
+      master_bus_gain_reduction,
+      recorded_frames,
+      event_overflow_count,
     } = await engine.getContextPointers()
     return {
       pointers: {
@@ -36,6 +50,10 @@ const requestHandlers: {
         audioWorkletPosition: audio_worklet_position,
         workerPerformance: worker_performance,
         workerPosition: worker_position,
+        masterMeter: master_meter,
+        masterBusGainReduction: master_bus_gain_reduction,
+        recordedFrames: recorded_frames,
+        eventOverflowCount: event_overflow_count,
       },
     }
   },
@@ -71,19 +89,86 @@ const requestHandlers: {
     engine!.sendMessageToModule(moduleHandle, message)
     return {}
   },
+  loadWavetable: ({ moduleHandle, samples, length, frames }) => {
+    engine!.loadWavetable(moduleHandle, samples, length, frames)
+    return {}
+  },
+  clearModuleFault: ({ moduleHandle }) => {
+    engine!.clearModuleFault(moduleHandle)
+    return {}
+  },
+  setMasterBus: ({ enabled, ceiling }) => {
+    engine!.setMasterBus(enabled, ceiling)
+    return {}
+  },
+  resetIntegratedLoudness: () => {
+    engine!.resetIntegratedLoudness()
+    return {}
+  },
+  armRecording: ({ preRollSeconds }) => {
+    try {
+      engine!.armRecording(preRollSeconds)
+      return { error: null }
+    } catch (error) {
+      return { error: String(error) }
+    }
+  },
+  startRecording: () => {
+    engine!.startRecording()
+    stopCollectingRecording()
+    collectRecordingInterval = setInterval(
+      () => engine!.collectRecording(),
+      1000
+    )
+    return {}
+  },
+  stopRecording: () => {
+    stopCollectingRecording()
+    engine!.stopRecording()
+    return {}
+  },
+  exportRecording: () => {
+    stopCollectingRecording()
+    const wav = engine!.exportRecording()
+    return { wav, truncated: engine!.isRecordingTruncated() }
+  },
+  setReferenceFrequency: ({ freq }) => {
+    try {
+      engine!.setReferenceFrequency(freq)
+      return { error: null }
+    } catch (error) {
+      return { error: String(error) }
+    }
+  },
+  // Errors in parsing the files are handed back to the client, as it can't
+  // otherwise tell them from a request that was never answered.
+  setTuning: ({ scl, kbm }) => {
+    try {
+      engine!.setTuning(scl ?? undefined, kbm ?? undefined)
+      return { error: null }
+    } catch (error) {
+      return { error: String(error) }
+    }
+  },
 }
 
 setInterval(() => {
   if (!engine) return
 
-  const events: { id: number; event: ModuleEvent<Module> }[] =
-    engine.collectModuleEvents()
+  const events: {
+    quantum: number
+    sample_offset: number
+    id: number
+    event: ModuleEvent<Module>
+  }[] = engine.collectModuleEvents()
 
-  for (const { id, event } of events) {
+  for (const { quantum, sample_offset, id, event } of events) {
     const engineEvent: EngineEvent = {
       type: 'moduleEvent',
       moduleHandle: id,
       message: event,
+      quantum,
+      sampleOffset: sample_offset,
     }
 
     self.postMessage(engineEvent)
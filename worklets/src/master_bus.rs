@@ -0,0 +1,139 @@
+use std::f32::consts::PI;
+
+use crate::{
+  audio_buffer::AudioBuffer,
+  modulate_core::{INV_SAMPLE_RATE, SAMPLE_RATE_F32},
+};
+
+// Length of the limiter lookahead window in samples. The latency added to the output is one sample
+// less than this.
+const LOOKAHEAD: usize = 64;
+const RELEASE_TIME: f32 = 0.1;
+const DC_BLOCKER_CUTOFF: f32 = 10.0;
+
+#[derive(Default)]
+struct DcBlocker {
+  previous_input: f32,
+  previous_output: f32,
+}
+
+impl DcBlocker {
+  fn step(&mut self, input: f32, pole: f32) -> f32 {
+    let output = input - self.previous_input + pole * self.previous_output;
+    self.previous_input = input;
+    self.previous_output = output;
+
+    output
+  }
+}
+
+// Optional safety chain applied to the summed output of all `AudioOut` modules. Removes DC offset
+// and limits the signal to the ceiling using a lookahead brickwall limiter: the gain required by
+// each sample is held over the lookahead window and then smoothed with a moving average of the same
+// length, so the gain has fully ramped down by the time a peak leaves the delay line.
+pub struct MasterBus {
+  enabled: bool,
+  ceiling: f32,
+
+  dc_blockers: [DcBlocker; 2],
+  delay_lines: [[f32; LOOKAHEAD]; 2],
+  required_gains: [f32; LOOKAHEAD],
+  held_gains: [f32; LOOKAHEAD],
+  held_gain_sum: f64,
+  released_gain: f32,
+  position: usize,
+
+  // Current gain reduction in decibels, read by the main thread through the pointer returned from
+  // `get_context_pointers`.
+  gain_reduction: f32,
+}
+
+impl Default for MasterBus {
+  fn default() -> Self {
+    MasterBus::new()
+  }
+}
+
+impl MasterBus {
+  pub fn new() -> MasterBus {
+    MasterBus {
+      enabled: false,
+      ceiling: 1.0,
+
+      dc_blockers: [DcBlocker::default(), DcBlocker::default()],
+      delay_lines: [[0.0; LOOKAHEAD]; 2],
+      required_gains: [1.0; LOOKAHEAD],
+      held_gains: [1.0; LOOKAHEAD],
+      held_gain_sum: LOOKAHEAD as f64,
+      released_gain: 1.0,
+      position: 0,
+
+      gain_reduction: 0.0,
+    }
+  }
+
+  pub fn gain_reduction_ptr(&self) -> usize {
+    &self.gain_reduction as *const f32 as usize
+  }
+
+  pub fn set_enabled(&mut self, enabled: bool, ceiling_db: f32) {
+    if enabled != self.enabled {
+      *self = MasterBus::new();
+    }
+
+    self.enabled = enabled;
+    self.ceiling = f32::powf(10.0, ceiling_db.min(0.0) / 20.0);
+  }
+
+  pub fn process(&mut self, left: &mut AudioBuffer, right: &mut AudioBuffer) {
+    if !self.enabled {
+      return;
+    }
+
+    let pole = 1.0 - 2.0 * PI * DC_BLOCKER_CUTOFF * INV_SAMPLE_RATE;
+    let release = 1.0 - f32::exp(-1.0 / (RELEASE_TIME * SAMPLE_RATE_F32));
+
+    let mut min_gain = 1.0f32;
+
+    for sample in 0..left.len() {
+      let input_l = self.dc_blockers[0].step(left[sample], pole);
+      let input_r = self.dc_blockers[1].step(right[sample], pole);
+
+      let peak = input_l.abs().max(input_r.abs());
+      self.required_gains[self.position] = if peak > self.ceiling {
+        self.ceiling / peak
+      } else {
+        1.0
+      };
+
+      let held_gain = self
+        .required_gains
+        .iter()
+        .fold(1.0f32, |min, &gain| min.min(gain));
+
+      // The release is applied before the moving average, and never exceeds the held gain, so the
+      // limiting is still guaranteed to be in effect for the peaks in the delay line.
+      self.released_gain = held_gain.min(self.released_gain + (1.0 - self.released_gain) * release);
+
+      self.held_gain_sum += (self.released_gain - self.held_gains[self.position]) as f64;
+      self.held_gains[self.position] = self.released_gain;
+
+      let gain = (self.held_gain_sum / LOOKAHEAD as f64) as f32;
+      min_gain = min_gain.min(gain);
+
+      // The gain covers the required gains of the last `LOOKAHEAD` samples, the oldest of which is
+      // the one about to leave the delay line.
+      self.delay_lines[0][self.position] = input_l;
+      self.delay_lines[1][self.position] = input_r;
+      self.position = (self.position + 1) % LOOKAHEAD;
+
+      left[sample] = self.delay_lines[0][self.position] * gain;
+      right[sample] = self.delay_lines[1][self.position] * gain;
+    }
+
+    // Recompute the sum once per quantum so the rounding errors of the running sum won't pile up.
+    self.held_gain_sum = self.held_gains.iter().map(|&gain| gain as f64).sum();
+
+    self.gain_reduction = -20.0 * f32::log10(min_gain);
+  }
+}
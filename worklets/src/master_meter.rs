@@ -0,0 +1,316 @@
+use std::f32::consts::PI;
+
+use crate::{
+  audio_buffer::AudioBuffer,
+  filters::biquad_filter::BiquadFilter,
+  modulate_core::{QUANTUM_SIZE, SAMPLE_RATE, SAMPLE_RATE_F32},
+};
+
+// Loudness windows and the gating block hop as defined in EBU R128, rounded to whole quanta.
+const MOMENTARY_QUANTA: usize = (SAMPLE_RATE * 4 / 10 + QUANTUM_SIZE / 2) / QUANTUM_SIZE;
+const SHORT_TERM_QUANTA: usize = (SAMPLE_RATE * 3 + QUANTUM_SIZE / 2) / QUANTUM_SIZE;
+const GATING_HOP_QUANTA: usize = (SAMPLE_RATE / 10 + QUANTUM_SIZE / 2) / QUANTUM_SIZE;
+
+// Gated blocks are counted in a histogram of 0.1 LU wide bins between the absolute gate and
+// +30 LUFS, so integrated loudness can be measured indefinitely without allocating.
+const ABSOLUTE_GATE: f32 = -70.0;
+const RELATIVE_GATE: f32 = -10.0;
+const HISTOGRAM_BINS: usize = 1000;
+const HISTOGRAM_BIN_WIDTH: f32 = 0.1;
+
+const TRUE_PEAK_OVERSAMPLE: usize = 4;
+const TRUE_PEAK_TAPS: usize = 12;
+
+// Time constants of the sample peak release and the RMS averaging, in seconds.
+const PEAK_RELEASE_TIME: f32 = 1.5;
+const RMS_WINDOW_TIME: f32 = 0.3;
+
+// Written by the barrier leader after each quantum and read by the main thread through the pointer
+// returned from `get_context_pointers`. Levels are linear, loudness values are in LUFS.
+#[repr(C)]
+pub struct MasterMeterReadings {
+  pub sample_peak: [f32; 2],
+  pub rms: [f32; 2],
+  pub true_peak: [f32; 2],
+  pub momentary_loudness: f32,
+  pub short_term_loudness: f32,
+  pub integrated_loudness: f32,
+}
+
+impl Default for MasterMeterReadings {
+  fn default() -> Self {
+    MasterMeterReadings {
+      sample_peak: [0.0; 2],
+      rms: [0.0; 2],
+      true_peak: [0.0; 2],
+      momentary_loudness: f32::NEG_INFINITY,
+      short_term_loudness: f32::NEG_INFINITY,
+      integrated_loudness: f32::NEG_INFINITY,
+    }
+  }
+}
+
+fn energy_to_loudness(energy: f64) -> f32 {
+  if energy <= 0.0 {
+    return f32::NEG_INFINITY;
+  }
+
+  -0.691 + 10.0 * f64::log10(energy) as f32
+}
+
+fn loudness_to_energy(loudness: f32) -> f64 {
+  f64::powf(10.0, (loudness as f64 + 0.691) / 10.0)
+}
+
+// The two stage K-weighting filter from ITU-R BS.1770, with the coefficients derived for the
+// engine sample rate.
+fn k_weighting_filters() -> [BiquadFilter; 2] {
+  let mut shelf = BiquadFilter::new();
+  {
+    let f0 = 1681.9745;
+    let gain = 3.9998438;
+    let q = 0.70717525;
+
+    let k = f32::tan(PI * f0 / SAMPLE_RATE_F32);
+    let vh = f32::powf(10.0, gain / 20.0);
+    let vb = f32::powf(vh, 0.49966677);
+
+    shelf.set_coefficients(
+      [
+        vh + vb * k / q + k * k,
+        2.0 * (k * k - vh),
+        vh - vb * k / q + k * k,
+      ],
+      [
+        1.0 + k / q + k * k,
+        2.0 * (k * k - 1.0),
+        1.0 - k / q + k * k,
+      ],
+    );
+  }
+
+  let mut highpass = BiquadFilter::new();
+  {
+    let f0 = 38.13547;
+    let q = 0.50032704;
+
+    let k = f32::tan(PI * f0 / SAMPLE_RATE_F32);
+    let a0 = 1.0 + k / q + k * k;
+
+    highpass.set_coefficients(
+      [1.0, -2.0, 1.0],
+      [1.0, 2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    );
+  }
+
+  [shelf, highpass]
+}
+
+// Polyphase windowed sinc interpolator used for the true peak measurement.
+fn true_peak_coefficients() -> [[f32; TRUE_PEAK_TAPS]; TRUE_PEAK_OVERSAMPLE] {
+  let mut coefficients = [[0.0; TRUE_PEAK_TAPS]; TRUE_PEAK_OVERSAMPLE];
+  let num_taps = TRUE_PEAK_TAPS * TRUE_PEAK_OVERSAMPLE;
+  let center = (num_taps - 1) as f32 / 2.0;
+
+  for tap in 0..num_taps {
+    let x = (tap as f32 - center) / TRUE_PEAK_OVERSAMPLE as f32;
+    let sinc = if x.abs() < f32::EPSILON {
+      1.0
+    } else {
+      f32::sin(PI * x) / (PI * x)
+    };
+    let n = tap as f32 / (num_taps - 1) as f32;
+    let blackman_window = 0.42 - 0.5 * f32::cos(2.0 * PI * n) + 0.08 * f32::cos(4.0 * PI * n);
+
+    coefficients[tap % TRUE_PEAK_OVERSAMPLE][tap / TRUE_PEAK_OVERSAMPLE] = sinc * blackman_window;
+  }
+
+  coefficients
+}
+
+struct ChannelMeter {
+  k_weighting: [BiquadFilter; 2],
+  history: [f32; TRUE_PEAK_TAPS],
+  mean_square: f32,
+}
+
+impl ChannelMeter {
+  fn new() -> ChannelMeter {
+    ChannelMeter {
+      k_weighting: k_weighting_filters(),
+      history: [0.0; TRUE_PEAK_TAPS],
+      mean_square: 0.0,
+    }
+  }
+}
+
+pub struct MasterMeter {
+  readings: MasterMeterReadings,
+  channels: [ChannelMeter; 2],
+  true_peak_coefficients: [[f32; TRUE_PEAK_TAPS]; TRUE_PEAK_OVERSAMPLE],
+
+  // Sum of the K-weighted channel energies of each quantum, for the duration of the short-term
+  // window.
+  quantum_energies: Vec<f64>,
+  energy_position: usize,
+  measured_quanta: usize,
+  momentary_energy: f64,
+  short_term_energy: f64,
+
+  histogram: Vec<u32>,
+  histogram_energies: Vec<f64>,
+}
+
+impl Default for MasterMeter {
+  fn default() -> Self {
+    MasterMeter::new()
+  }
+}
+
+impl MasterMeter {
+  pub fn new() -> MasterMeter {
+    MasterMeter {
+      readings: MasterMeterReadings::default(),
+      channels: [ChannelMeter::new(), ChannelMeter::new()],
+      true_peak_coefficients: true_peak_coefficients(),
+
+      quantum_energies: vec![0.0; SHORT_TERM_QUANTA],
+      energy_position: 0,
+      measured_quanta: 0,
+      momentary_energy: 0.0,
+      short_term_energy: 0.0,
+
+      histogram: vec![0; HISTOGRAM_BINS],
+      histogram_energies: (0..HISTOGRAM_BINS)
+        .map(|bin| loudness_to_energy(ABSOLUTE_GATE + (bin as f32 + 0.5) * HISTOGRAM_BIN_WIDTH))
+        .collect(),
+    }
+  }
+
+  pub fn readings_ptr(&self) -> usize {
+    &self.readings as *const MasterMeterReadings as usize
+  }
+
+  pub fn reset_integrated_loudness(&mut self) {
+    self.histogram.fill(0);
+    self.readings.integrated_loudness = f32::NEG_INFINITY;
+  }
+
+  pub fn process(&mut self, left: &AudioBuffer, right: &AudioBuffer) {
+    let peak_release = f32::exp(-(QUANTUM_SIZE as f32) / (PEAK_RELEASE_TIME * SAMPLE_RATE_F32));
+    let rms_coefficient = 1.0 - f32::exp(-1.0 / (RMS_WINDOW_TIME * SAMPLE_RATE_F32));
+
+    let mut quantum_energy = 0.0;
+
+    for (channel, buffer) in [left, right].into_iter().enumerate() {
+      let meter = &mut self.channels[channel];
+      let mut sample_peak = 0.0f32;
+      let mut true_peak = 0.0f32;
+      let mut energy = 0.0f32;
+
+      for &value in buffer.iter() {
+        sample_peak = sample_peak.max(value.abs());
+
+        meter.mean_square += (value * value - meter.mean_square) * rms_coefficient;
+
+        meter.history.rotate_right(1);
+        meter.history[0] = value;
+        for phase in self.true_peak_coefficients.iter() {
+          let interpolated: f32 = phase
+            .iter()
+            .zip(meter.history.iter())
+            .map(|(coefficient, value)| coefficient * value)
+            .sum();
+          true_peak = true_peak.max(interpolated.abs());
+        }
+
+        let shelved = meter.k_weighting[0].step(value);
+        let weighted = meter.k_weighting[1].step(shelved);
+        energy += weighted * weighted;
+      }
+
+      quantum_energy += energy as f64;
+
+      let readings = &mut self.readings;
+      readings.sample_peak[channel] = sample_peak.max(readings.sample_peak[channel] * peak_release);
+      readings.true_peak[channel] = true_peak
+        .max(sample_peak)
+        .max(readings.true_peak[channel] * peak_release);
+      readings.rms[channel] = meter.mean_square.sqrt();
+    }
+
+    self.push_quantum_energy(quantum_energy);
+  }
+
+  fn window_energy(&self, num_quanta: usize) -> f64 {
+    (0..num_quanta)
+      .map(|i| {
+        self.quantum_energies
+          [(self.energy_position + SHORT_TERM_QUANTA - 1 - i) % SHORT_TERM_QUANTA]
+      })
+      .sum()
+  }
+
+  fn push_quantum_energy(&mut self, energy: f64) {
+    let momentary_tail =
+      (self.energy_position + SHORT_TERM_QUANTA - MOMENTARY_QUANTA) % SHORT_TERM_QUANTA;
+    self.momentary_energy += energy - self.quantum_energies[momentary_tail];
+    self.short_term_energy += energy - self.quantum_energies[self.energy_position];
+
+    self.quantum_energies[self.energy_position] = energy;
+    self.energy_position = (self.energy_position + 1) % SHORT_TERM_QUANTA;
+    self.measured_quanta += 1;
+
+    // The running sums accumulate rounding errors, so sum the windows again every now and then.
+    if self.energy_position == 0 {
+      self.momentary_energy = self.window_energy(MOMENTARY_QUANTA);
+      self.short_term_energy = self.window_energy(SHORT_TERM_QUANTA);
+    }
+
+    let momentary_loudness =
+      energy_to_loudness(self.momentary_energy / (MOMENTARY_QUANTA * QUANTUM_SIZE) as f64);
+    let short_term_loudness =
+      energy_to_loudness(self.short_term_energy / (SHORT_TERM_QUANTA * QUANTUM_SIZE) as f64);
+
+    self.readings.momentary_loudness = momentary_loudness;
+    self.readings.short_term_loudness = short_term_loudness;
+
+    if self.measured_quanta >= MOMENTARY_QUANTA && self.measured_quanta % GATING_HOP_QUANTA == 0 {
+      if momentary_loudness >= ABSOLUTE_GATE {
+        let bin = ((momentary_loudness - ABSOLUTE_GATE) / HISTOGRAM_BIN_WIDTH) as usize;
+        self.histogram[bin.min(HISTOGRAM_BINS - 1)] += 1;
+      }
+
+      self.readings.integrated_loudness = self.integrated_loudness();
+    }
+  }
+
+  fn integrated_loudness(&self) -> f32 {
+    let gated_mean = |from_bin: usize| {
+      let (energy, count) = self.histogram[from_bin..]
+        .iter()
+        .zip(self.histogram_energies[from_bin..].iter())
+        .fold((0.0, 0), |(energy, count), (&blocks, &bin_energy)| {
+          (energy + blocks as f64 * bin_energy, count + blocks)
+        });
+
+      if count == 0 {
+        0.0
+      } else {
+        energy / count as f64
+      }
+    };
+
+    let relative_gate = energy_to_loudness(gated_mean(0)) + RELATIVE_GATE;
+    if relative_gate == f32::NEG_INFINITY {
+      return f32::NEG_INFINITY;
+    }
+
+    let relative_gate_bin = (((relative_gate - ABSOLUTE_GATE) / HISTOGRAM_BIN_WIDTH)
+      .ceil()
+      .max(0.0) as usize)
+      .min(HISTOGRAM_BINS - 1);
+
+    energy_to_loudness(gated_mean(relative_gate_bin))
+  }
+}
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use crate::audio_input::AudioInput;
 use crate::audio_output::AudioOutput;
 use crate::audio_param::AudioParam;
-use crate::vec;
+use crate::modules::wavetable::WavetableMipmap;
 
 pub type ModuleId = u32;
 pub type ConnectionId = u32;
@@ -11,32 +11,53 @@ pub type OutputId = usize;
 pub type ParameterId = usize;
 pub type InputId = usize;
 
-#[derive(Copy, Clone, Default, Serialize)]
-pub struct Ball {
-  pub pos: vec::Vec2,
-  pub vel: vec::Vec2,
+#[derive(Clone, Copy, Serialize)]
+pub enum ModuleFaultReason {
+  NonFinite,
+  Panic,
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Copy, Serialize)]
 #[serde(tag = "type")]
 pub enum ModuleEvent {
-  SequencerAdvance { position: usize },
-  BouncyBoiUpdate { balls: [Ball; 3], phase: f32 },
-  SamplerAllocateSuccess { ptr: usize },
+  SamplerAllocateSuccess {
+    ptr: usize,
+  },
+  ModuleFault {
+    reason: ModuleFaultReason,
+    count: u32,
+  },
 }
 
-#[derive(Serialize)]
-pub struct ModuleEventWithId {
-  pub id: ModuleId,
-  pub event: ModuleEvent,
+fn default_probability() -> f32 {
+  1.0
 }
 
+// The fields added after the first version default to a plain step, so that older patches still
+// load.
 #[derive(Deserialize)]
 pub struct NamedNote {
   pub name: String,
   pub octave: f32,
   pub gate: bool,
   pub glide: bool,
+  #[serde(default = "default_probability")]
+  pub probability: f32,
+  #[serde(default)]
+  pub ratchets: u32,
+  // Glide time of the step in seconds, 0 to use the glide time of the sequencer.
+  #[serde(default)]
+  pub slide: f32,
+  #[serde(default)]
+  pub skip: bool,
+  #[serde(default)]
+  pub accent: bool,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+pub struct SequencerChainEntry {
+  pub pattern: usize,
+  pub repeats: u32,
 }
 
 #[derive(Deserialize)]
@@ -46,19 +67,56 @@ pub struct PianoRollNote {
   pub length: f32,
 }
 
+#[derive(Clone, Copy, Deserialize)]
+pub struct GateStep {
+  pub gate: bool,
+  pub velocity: f32,
+  pub probability: f32,
+  pub ratchets: u32,
+}
+
 #[derive(Deserialize)]
 #[serde(tag = "type")]
 pub enum ModuleMessage {
-  SequencerSetNotes { notes: Vec<NamedNote> },
+  SequencerSetNotes {
+    #[serde(default)]
+    pattern: usize,
+    notes: Vec<NamedNote>,
+  },
+  SequencerReset,
+  // An empty chain plays the pattern chosen by the pattern parameter.
+  SequencerSetChain {
+    chain: Vec<SequencerChainEntry>,
+  },
 
   ClockReset,
-  ClockSetRunning { running: bool },
-
-  MidiMessage { message: u32 },
-
-  SamplerAllocate { size: usize },
-
-  PianoRollSetNotes { notes: Vec<PianoRollNote> },
+  ClockSetRunning {
+    running: bool,
+  },
+
+  MidiMessage {
+    message: u32,
+  },
+
+  SamplerAllocate {
+    size: usize,
+  },
+
+  PianoRollSetNotes {
+    notes: Vec<PianoRollNote>,
+  },
+
+  // Enabled notes of the scale, from C to B.
+  QuantizerSetNotes {
+    notes: [bool; 12],
+  },
+
+  // Steps past the length of the track are kept, and play once the track is made longer.
+  GateSequencerSetTrack {
+    track: usize,
+    length: usize,
+    steps: Vec<GateStep>,
+  },
 }
 
 pub trait Module {
@@ -95,6 +153,18 @@ pub trait Module {
     ptr
   }
 
+  fn connect_output(&mut self, output: OutputId) {
+    let mut outputs = self.get_outputs();
+    let buffer = outputs.get_mut(output).unwrap();
+    buffer.connections += 1;
+  }
+
+  fn disconnect_output(&mut self, output: OutputId) {
+    let mut outputs = self.get_outputs();
+    let buffer = outputs.get_mut(output).unwrap();
+    buffer.connections -= 1;
+  }
+
   fn set_input_buffer_ptr(&mut self, input: InputId, buffer_ptr: *const AudioOutput) {
     let mut inputs = self.get_inputs();
     let buffer = inputs.get_mut(input).unwrap();
@@ -119,11 +189,21 @@ pub trait Module {
     buffer.modulation.reset_ptr();
   }
 
-  fn pop_event(&mut self) -> Option<ModuleEvent> {
+  // Events are popped in the order they happened, along with the sample of the quantum they
+  // happened at.
+  fn pop_event(&mut self) -> Option<(usize, ModuleEvent)> {
     None
   }
 
+  // Exchanges the tables of a wavetable oscillator with `mipmap`, which the engine builds outside
+  // of the module lock. Other modules ignore it.
+  fn swap_wavetable(&mut self, _mipmap: &mut Option<Box<WavetableMipmap>>) {}
+
   fn on_message(&mut self, _message: ModuleMessage) {
     panic!("module received a message when no handler is implemented");
   }
+
+  // Called when the module is quarantined, should clear any internal state (filter memory, delay
+  // lines, phases) which might have been corrupted by non-finite values.
+  fn reset(&mut self) {}
 }
@@ -0,0 +1,85 @@
+use crate::module::{Module, ModuleEvent, ModuleFaultReason};
+
+// Amount of consecutive quanta a module may output non-finite values before it's quarantined.
+const QUARANTINE_THRESHOLD: u32 = 4;
+
+// Inspects the output buffers of a module after it has been processed. Non-finite values are
+// replaced with silence so that they won't propagate to the modules downstream. A module which
+// keeps producing them, or which has panicked, is faulted: it's no longer processed and only
+// outputs silence until the fault is cleared. Each fault is reported once.
+#[derive(Default)]
+pub struct ModuleGuard {
+  faulty_quanta: u32,
+  fault_count: u32,
+  faulted: bool,
+  pending_event: Option<ModuleEvent>,
+}
+
+impl ModuleGuard {
+  pub fn inspect(&mut self, module: &mut Box<dyn Module>, flush_denormals: bool) {
+    let mut non_finite_samples = 0;
+
+    for output in module.get_outputs() {
+      for value in output.write_buffer_mut().iter_mut() {
+        if !value.is_finite() {
+          *value = 0.0;
+          non_finite_samples += 1;
+        } else if flush_denormals && value.is_subnormal() {
+          *value = 0.0;
+        }
+      }
+    }
+
+    if non_finite_samples == 0 {
+      self.faulty_quanta = 0;
+      return;
+    }
+
+    self.faulty_quanta += 1;
+
+    if self.faulty_quanta >= QUARANTINE_THRESHOLD {
+      self.faulty_quanta = 0;
+      self.faulted = true;
+
+      self.silence(module);
+      self.report(ModuleFaultReason::NonFinite);
+    }
+  }
+
+  pub fn fault(&mut self, module: &mut Box<dyn Module>) {
+    self.faulted = true;
+
+    self.silence(module);
+    self.report(ModuleFaultReason::Panic);
+  }
+
+  // Lets a faulted module be processed again, starting over from a clean state.
+  pub fn clear(&mut self, module: &mut Box<dyn Module>) {
+    module.reset();
+
+    self.faulty_quanta = 0;
+    self.faulted = false;
+  }
+
+  pub fn is_faulted(&self) -> bool {
+    self.faulted
+  }
+
+  pub fn silence(&mut self, module: &mut Box<dyn Module>) {
+    for output in module.get_outputs() {
+      output.write_buffer_mut().fill(0.0);
+    }
+  }
+
+  fn report(&mut self, reason: ModuleFaultReason) {
+    self.fault_count += 1;
+    self.pending_event = Some(ModuleEvent::ModuleFault {
+      reason,
+      count: self.fault_count,
+    });
+  }
+
+  pub fn pop_event(&mut self) -> Option<ModuleEvent> {
+    self.pending_event.take()
+  }
+}
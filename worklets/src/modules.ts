@@ -1,4 +1,4 @@
-import { Note, Vec2 } from '@modulate/common/types'
+import { GateStep, Note, SequencerChainEntry } from '@modulate/common/types'
 
 type ModuleTypeOf<Module, Messages = never, Events = never> = Module & {
   events: Events
@@ -16,8 +16,20 @@ export type AudioOut = ModuleTypeOf<typeof AudioOut>
 export const Oscillator = {
   name: 'Oscillator',
   inputs: ['sync'],
-  parameters: ['cv', 'fm', 'pw', 'fine', 'level'],
-  outputs: ['sin', 'tri', 'saw', 'sqr'],
+  parameters: [
+    'cv',
+    'fm',
+    'pw',
+    'fine',
+    'level',
+    'linFm',
+    'pm',
+    'syncMode',
+    'voices',
+    'detune',
+    'spread',
+  ],
+  outputs: ['sin', 'tri', 'saw', 'sqr', 'left', 'right'],
 } as const
 export type Oscillator = ModuleTypeOf<typeof Oscillator>
 
@@ -96,14 +108,15 @@ export type PowShaper = ModuleTypeOf<typeof PowShaper>
 
 export const Sequencer = {
   name: 'Sequencer',
-  inputs: ['gate'],
-  parameters: ['length', 'glide'],
-  outputs: ['cv', 'gate'],
+  inputs: ['gate', 'reset'],
+  parameters: ['length', 'glide', 'direction', 'pattern'],
+  outputs: ['cv', 'gate', 'accent'],
 } as const
 export type Sequencer = ModuleTypeOf<
   typeof Sequencer,
-  { type: 'SequencerSetNotes'; notes: Note[] },
-  { type: 'SequencerAdvance'; position: number }
+  | { type: 'SequencerSetNotes'; pattern: number; notes: Note[] }
+  | { type: 'SequencerReset' }
+  | { type: 'SequencerSetChain'; chain: SequencerChainEntry[] }
 >
 
 export const ADSR = {
@@ -171,15 +184,7 @@ export const BouncyBoi = {
   parameters: ['speed', 'gravity'],
   outputs: ['trig0', 'trig1', 'trig2', 'vel0', 'vel1', 'vel2'],
 } as const
-export type BouncyBoi = ModuleTypeOf<
-  typeof BouncyBoi,
-  never,
-  {
-    type: 'BouncyBoiUpdate'
-    balls: { pos: Vec2; vel: Vec2 }[]
-    phase: number
-  }
->
+export type BouncyBoi = ModuleTypeOf<typeof BouncyBoi>
 
 export const Sampler = {
   name: 'Sampler',
@@ -287,6 +292,196 @@ export const Sideq = {
 } as const
 export type Sideq = ModuleTypeOf<typeof Sideq>
 
+export const FMVoice = {
+  name: 'FMVoice',
+  inputs: ['gate'],
+  parameters: [
+    'cv',
+    'algorithm',
+    'feedback',
+    'level',
+    'ratio1',
+    'detune1',
+    'level1',
+    'attack1',
+    'decay1',
+    'sustain1',
+    'release1',
+    'ratio2',
+    'detune2',
+    'level2',
+    'attack2',
+    'decay2',
+    'sustain2',
+    'release2',
+    'ratio3',
+    'detune3',
+    'level3',
+    'attack3',
+    'decay3',
+    'sustain3',
+    'release3',
+    'ratio4',
+    'detune4',
+    'level4',
+    'attack4',
+    'decay4',
+    'sustain4',
+    'release4',
+  ],
+  outputs: ['output'],
+} as const
+export type FMVoice = ModuleTypeOf<typeof FMVoice>
+
+export const KarplusStrong = {
+  name: 'KarplusStrong',
+  inputs: ['trigger', 'exciter'],
+  parameters: [
+    'cv',
+    'damping',
+    'brightness',
+    'position',
+    'decay',
+    'bow',
+    'level',
+  ],
+  outputs: ['output'],
+} as const
+export type KarplusStrong = ModuleTypeOf<typeof KarplusStrong>
+
+export const ModalResonator = {
+  name: 'ModalResonator',
+  inputs: ['input'],
+  parameters: [
+    'cv',
+    'structure',
+    'brightness',
+    'damping',
+    'position',
+    'level',
+  ],
+  outputs: ['output'],
+} as const
+export type ModalResonator = ModuleTypeOf<typeof ModalResonator>
+
+export const Noise = {
+  name: 'Noise',
+  inputs: [],
+  parameters: ['level'],
+  outputs: ['white', 'pink', 'brown', 'blue', 'velvet'],
+} as const
+export type Noise = ModuleTypeOf<typeof Noise>
+
+export const SampleAndHold = {
+  name: 'SampleAndHold',
+  inputs: ['input', 'trigger'],
+  parameters: ['mode', 'rate', 'slew'],
+  outputs: ['output'],
+} as const
+export type SampleAndHold = ModuleTypeOf<typeof SampleAndHold>
+
+export const Chaos = {
+  name: 'Chaos',
+  inputs: [],
+  parameters: ['rate', 'chaos'],
+  outputs: ['smooth', 'stepped', 'x', 'y', 'z', 'logistic'],
+} as const
+export type Chaos = ModuleTypeOf<typeof Chaos>
+
+export const Quantizer = {
+  name: 'Quantizer',
+  inputs: ['input'],
+  parameters: ['root', 'transpose'],
+  outputs: ['cv', 'trigger'],
+} as const
+export type Quantizer = ModuleTypeOf<
+  typeof Quantizer,
+  { type: 'QuantizerSetNotes'; notes: boolean[] }
+>
+
+export const GateSequencer = {
+  name: 'GateSequencer',
+  inputs: ['clock', 'reset'],
+  parameters: ['gateLength'],
+  outputs: [
+    'gate0',
+    'gate1',
+    'gate2',
+    'gate3',
+    'gate4',
+    'gate5',
+    'gate6',
+    'gate7',
+  ],
+} as const
+export type GateSequencer = ModuleTypeOf<
+  typeof GateSequencer,
+  {
+    type: 'GateSequencerSetTrack'
+    track: number
+    length: number
+    steps: GateStep[]
+  }
+>
+
+export const Euclidean = {
+  name: 'Euclidean',
+  inputs: ['clock', 'reset'],
+  parameters: [
+    'steps0',
+    'pulses0',
+    'rotation0',
+    'steps1',
+    'pulses1',
+    'rotation1',
+    'steps2',
+    'pulses2',
+    'rotation2',
+    'steps3',
+    'pulses3',
+    'rotation3',
+  ],
+  outputs: ['gate0', 'gate1', 'gate2', 'gate3'],
+} as const
+export type Euclidean = ModuleTypeOf<typeof Euclidean>
+
+export const TuringMachine = {
+  name: 'TuringMachine',
+  inputs: ['clock', 'reset'],
+  parameters: ['length', 'change', 'scale', 'seed'],
+  outputs: ['cv', 'gate0', 'gate1', 'gate2', 'gate3'],
+} as const
+export type TuringMachine = ModuleTypeOf<typeof TuringMachine>
+
+export const Arpeggiator = {
+  name: 'Arpeggiator',
+  inputs: [
+    'clock',
+    'cv0',
+    'gate0',
+    'cv1',
+    'gate1',
+    'cv2',
+    'gate2',
+    'cv3',
+    'gate3',
+  ],
+  parameters: ['mode', 'octaves', 'gateLength', 'latch'],
+  outputs: ['cv', 'gate'],
+} as const
+export type Arpeggiator = ModuleTypeOf<
+  typeof Arpeggiator,
+  { type: 'MidiMessage'; message: number }
+>
+
+export const Wavetable = {
+  name: 'Wavetable',
+  inputs: [],
+  parameters: ['cv', 'fm', 'fine', 'position', 'level'],
+  outputs: ['out'],
+} as const
+export type Wavetable = ModuleTypeOf<typeof Wavetable>
+
 export const modules = {
   AudioOut,
   Oscillator,
@@ -311,6 +506,18 @@ export const modules = {
   EQ3,
   RingMod,
   Sideq,
+  Wavetable,
+  FMVoice,
+  KarplusStrong,
+  ModalResonator,
+  Noise,
+  SampleAndHold,
+  Chaos,
+  Quantizer,
+  Arpeggiator,
+  GateSequencer,
+  Euclidean,
+  TuringMachine,
 } as const
 
 export type Module =
@@ -337,6 +544,18 @@ export type Module =
   | EQ3
   | RingMod
   | Sideq
+  | Wavetable
+  | FMVoice
+  | KarplusStrong
+  | ModalResonator
+  | Noise
+  | SampleAndHold
+  | Chaos
+  | Quantizer
+  | Arpeggiator
+  | GateSequencer
+  | Euclidean
+  | TuringMachine
 
 export type ModuleName = Module['name']
 
@@ -353,13 +572,13 @@ export const MODULE_PARAMETER_COUNT: {
   FDNReverb: 5,
   Chorus: 5,
   AudioOut: 1,
-  Oscillator: 5,
+  Oscillator: 11,
   BiquadFilter: 6,
   Mixer: 8,
   Gain: 1,
   Limiter: 1,
   PowShaper: 3,
-  Sequencer: 2,
+  Sequencer: 4,
   ADSR: 8,
   Delay: 4,
   Clock: 10,
@@ -371,4 +590,16 @@ export const MODULE_PARAMETER_COUNT: {
   EQ3: 9,
   RingMod: 1,
   Sideq: 0,
+  Wavetable: 5,
+  FMVoice: 32,
+  KarplusStrong: 7,
+  ModalResonator: 6,
+  Noise: 1,
+  SampleAndHold: 3,
+  Chaos: 2,
+  Quantizer: 2,
+  Arpeggiator: 4,
+  GateSequencer: 1,
+  Euclidean: 12,
+  TuringMachine: 4,
 }
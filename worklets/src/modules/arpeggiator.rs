@@ -0,0 +1,289 @@
+use crate::audio_input::AudioInput;
+use crate::audio_output::AudioOutput;
+use crate::audio_param::{AudioParam, AudioParamModulationType};
+use crate::{
+  edge_detector::EdgeDetector,
+  modulate_core::QUANTUM_SIZE,
+  module::{Module, ModuleMessage},
+  rng::Rng,
+  tuning::{Tuning, NUM_NOTES},
+};
+
+const MIDI_NOTE_OFF: u32 = 0b1000;
+const MIDI_NOTE_ON: u32 = 0b1001;
+
+const NUM_INPUTS: usize = 4;
+const MAX_NOTES: usize = NUM_NOTES + NUM_INPUTS;
+
+// Any other mode goes up through the notes.
+const MODE_DOWN: usize = 1;
+const MODE_UP_DOWN: usize = 2;
+const MODE_RANDOM: usize = 3;
+const MODE_AS_PLAYED: usize = 4;
+
+const MAX_OCTAVES: usize = 4;
+
+#[derive(Clone, Copy, PartialEq)]
+enum NoteSource {
+  Midi(usize),
+  Input(usize),
+}
+
+#[derive(Clone, Copy)]
+struct HeldNote {
+  source: NoteSource,
+  voltage: f32,
+}
+
+// Steps through the held notes on every rising edge of the clock. The notes come from MIDI and
+// from the CV/gate input pairs, whose CV is sampled when their gate opens.
+pub struct Arpeggiator {
+  clock_input: AudioInput,
+  cv_inputs: [AudioInput; NUM_INPUTS],
+  gate_inputs: [AudioInput; NUM_INPUTS],
+  cv_output: AudioOutput,
+  gate_output: AudioOutput,
+
+  mode_param: AudioParam,
+  octaves_param: AudioParam,
+  gate_length_param: AudioParam,
+  latch_param: AudioParam,
+
+  clock_edge_detector: EdgeDetector,
+  gate_edge_detectors: [EdgeDetector; NUM_INPUTS],
+
+  // Notes currently held down, in the order they were played.
+  held_notes: Vec<HeldNote>,
+  // Notes played since all of the notes were last released, which keep playing while latched.
+  latched_notes: Vec<HeldNote>,
+  // The notes being arpeggiated, sorted for the modes which need it. Kept around to not allocate.
+  sorted_voltages: Vec<f32>,
+
+  step: usize,
+  rng: Rng,
+  voltage: f32,
+
+  // Samples since the last clock edge, and between the last two, which the gate length is
+  // relative to.
+  time: usize,
+  clock_period: usize,
+
+  tuning: *const Tuning,
+}
+
+impl Module for Arpeggiator {
+  fn process(&mut self, _quantum: u64) {
+    let is_latched = self.latch_param.at(0) >= 0.5;
+
+    for sample in 0..QUANTUM_SIZE {
+      for input in 0..NUM_INPUTS {
+        let edge = self.gate_edge_detectors[input].step(self.gate_inputs[input].at(sample));
+
+        if edge.rose() {
+          let voltage = self.cv_inputs[input].at(sample);
+          self.press(NoteSource::Input(input), voltage);
+        } else if edge.fell() {
+          self.release(NoteSource::Input(input));
+        }
+      }
+
+      let clock = self.clock_input.at(sample);
+      let edge = self.clock_edge_detector.step(clock);
+
+      if edge.rose() {
+        if self.time > 0 {
+          self.clock_period = self.time;
+        }
+        self.time = 0;
+
+        let mode = self.mode_param.at(sample).round() as usize;
+        let octaves = (self.octaves_param.at(sample).round() as usize).clamp(1, MAX_OCTAVES);
+        if let Some(voltage) = self.next_voltage(mode, octaves, is_latched) {
+          self.voltage = voltage;
+        }
+      }
+
+      let notes = if is_latched {
+        &self.latched_notes
+      } else {
+        &self.held_notes
+      };
+
+      // Until the clock period is known the gate follows the clock.
+      let gate_length = self.gate_length_param.at(sample).clamp(0.0, 1.0);
+      let is_gate_open = if self.clock_period == 0 {
+        edge.is_high()
+      } else {
+        (self.time as f32) < gate_length * self.clock_period as f32
+      };
+
+      self.cv_output[sample] = self.voltage;
+      self.gate_output[sample] = if is_gate_open && !notes.is_empty() {
+        1.0
+      } else {
+        0.0
+      };
+
+      self.time += 1;
+    }
+  }
+
+  fn on_message(&mut self, message: ModuleMessage) {
+    let ModuleMessage::MidiMessage { message } = message else {
+      return;
+    };
+
+    let message_type = (message >> 4) & 0b0000_1111;
+    let note = ((message >> 8) & 0b0111_1111) as usize;
+    let velocity = (message >> 16) & 0b0111_1111;
+
+    match message_type {
+      MIDI_NOTE_ON if velocity > 0 => {
+        // Notes left unmapped by the tuning aren't played.
+        let tuning = unsafe { &*self.tuning };
+        if let Some(voltage) = tuning.note_to_voltage(note as i32) {
+          self.press(NoteSource::Midi(note), voltage);
+        }
+      }
+      MIDI_NOTE_ON | MIDI_NOTE_OFF => self.release(NoteSource::Midi(note)),
+      _ => {}
+    }
+  }
+
+  fn get_inputs(&mut self) -> Vec<&mut AudioInput> {
+    let [cv0, cv1, cv2, cv3] = &mut self.cv_inputs;
+    let [gate0, gate1, gate2, gate3] = &mut self.gate_inputs;
+
+    vec![
+      &mut self.clock_input,
+      cv0,
+      gate0,
+      cv1,
+      gate1,
+      cv2,
+      gate2,
+      cv3,
+      gate3,
+    ]
+  }
+
+  fn get_parameters(&mut self) -> Vec<&mut AudioParam> {
+    vec![
+      &mut self.mode_param,
+      &mut self.octaves_param,
+      &mut self.gate_length_param,
+      &mut self.latch_param,
+    ]
+  }
+
+  fn get_outputs(&mut self) -> Vec<&mut AudioOutput> {
+    vec![&mut self.cv_output, &mut self.gate_output]
+  }
+
+  fn reset(&mut self) {
+    self.held_notes.clear();
+    self.latched_notes.clear();
+    self.step = 0;
+    self.time = 0;
+    self.clock_period = 0;
+  }
+}
+
+impl Arpeggiator {
+  pub fn new(tuning: &Tuning) -> Box<Arpeggiator> {
+    Box::new(Arpeggiator {
+      clock_input: AudioInput::default(),
+      cv_inputs: Default::default(),
+      gate_inputs: Default::default(),
+      cv_output: AudioOutput::default(),
+      gate_output: AudioOutput::default(),
+
+      mode_param: AudioParam::new(AudioParamModulationType::Additive),
+      octaves_param: AudioParam::new(AudioParamModulationType::Additive),
+      gate_length_param: AudioParam::new(AudioParamModulationType::Additive),
+      latch_param: AudioParam::new(AudioParamModulationType::Additive),
+
+      clock_edge_detector: EdgeDetector::default(),
+      gate_edge_detectors: Default::default(),
+
+      held_notes: Vec::with_capacity(MAX_NOTES),
+      latched_notes: Vec::with_capacity(MAX_NOTES),
+      sorted_voltages: Vec::with_capacity(MAX_NOTES),
+
+      step: 0,
+      rng: Rng::unique(),
+      voltage: 0.0,
+
+      time: 0,
+      clock_period: 0,
+
+      tuning,
+    })
+  }
+
+  fn press(&mut self, source: NoteSource, voltage: f32) {
+    self.release(source);
+
+    // Playing a note after all of them have been released starts a new latched chord, from the
+    // beginning of the pattern.
+    if self.held_notes.is_empty() {
+      self.latched_notes.clear();
+      self.step = 0;
+    }
+
+    self.latched_notes.retain(|note| note.source != source);
+
+    self.held_notes.push(HeldNote { source, voltage });
+    self.latched_notes.push(HeldNote { source, voltage });
+  }
+
+  fn release(&mut self, source: NoteSource) {
+    self.held_notes.retain(|note| note.source != source);
+  }
+
+  fn next_voltage(&mut self, mode: usize, octaves: usize, is_latched: bool) -> Option<f32> {
+    let notes = if is_latched {
+      &self.latched_notes
+    } else {
+      &self.held_notes
+    };
+
+    self.sorted_voltages.clear();
+    self
+      .sorted_voltages
+      .extend(notes.iter().map(|note| note.voltage));
+
+    if mode != MODE_AS_PLAYED {
+      self.sorted_voltages.sort_unstable_by(f32::total_cmp);
+    }
+
+    let num_notes = self.sorted_voltages.len();
+    if num_notes == 0 {
+      self.step = 0;
+      return None;
+    }
+
+    // The pattern goes through the notes in each of the octaves, each octave 1V above the
+    // previous one.
+    let length = num_notes * octaves;
+    let index = match mode {
+      MODE_DOWN => length - 1 - self.step % length,
+      // The top and bottom notes aren't repeated when turning around.
+      MODE_UP_DOWN if length > 1 => {
+        let position = self.step % (2 * length - 2);
+        if position < length {
+          position
+        } else {
+          2 * length - 2 - position
+        }
+      }
+      MODE_RANDOM => self.rng.get_u32() as usize % length,
+      // Up, and as played, which goes through the notes in the order they were played.
+      _ => self.step % length,
+    };
+
+    self.step = self.step.wrapping_add(1);
+
+    Some(self.sorted_voltages[index % num_notes] + (index / num_notes) as f32)
+  }
+}
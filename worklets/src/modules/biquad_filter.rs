@@ -57,6 +57,11 @@ impl Module for BiquadFilter {
   fn get_inputs(&mut self) -> Vec<&mut AudioInput> {
     vec![&mut self.input]
   }
+
+  fn reset(&mut self) {
+    self.lowpass.reset();
+    self.highpass.reset();
+  }
 }
 
 impl BiquadFilter {
@@ -1,30 +1,20 @@
 use crate::audio_output::AudioOutput;
 use crate::audio_param::AudioParam;
-use crate::{
-  modulate_core::QUANTUM_SIZE,
-  module::{Ball, Module, ModuleEvent},
-  vec::Vec2,
-};
-
-#[derive(Default)]
-struct Rng {
-  state: u64,
-}
+use crate::{modulate_core::QUANTUM_SIZE, module::Module, rng::Rng, ui_state::UiState, vec::Vec2};
 
-impl Rng {
-  fn get_u32(&mut self) -> u32 {
-    let oldstate = self.state;
-    // Advance internal state
-    self.state = oldstate.wrapping_mul(6364136223846793005u64) + 1u64;
-    // Calculate output function (XSH RR), uses old state for max ILP
-    let xorshifted: u32 = (((oldstate >> 18u64) ^ oldstate) >> 27u64) as u32;
-    let rot: u32 = (oldstate >> 59u64) as u32;
-    return (xorshifted >> rot) | (xorshifted << ((0u32.wrapping_sub(rot)) & 31));
-  }
+#[derive(Copy, Clone, Default)]
+#[repr(C)]
+struct Ball {
+  pos: Vec2,
+  vel: Vec2,
+}
 
-  fn get_f32(&mut self) -> f32 {
-    self.get_u32() as f32 * 2.3283064e-10
-  }
+// The balls and the rotation of the walls, read by the UI for drawing.
+#[derive(Copy, Clone, Default)]
+#[repr(C)]
+struct BouncyBoiUiState {
+  balls: [Ball; 3],
+  phase: f32,
 }
 
 pub struct BouncyBoi {
@@ -37,8 +27,7 @@ pub struct BouncyBoi {
   gravity: AudioParam,
 
   phase: f32,
-  cycle_count: usize,
-  events: Vec<ModuleEvent>,
+  ui_state: UiState<BouncyBoiUiState>,
 }
 
 #[derive(Copy, Clone, Default)]
@@ -110,19 +99,10 @@ impl Module for BouncyBoi {
       }
     }
 
-    self.cycle_count += 1;
-    // Each cycle is about 2.9ms, five cycles is a bit over 60hz
-    if self.cycle_count >= 5 {
-      self.cycle_count = 0;
-      self.events.push(ModuleEvent::BouncyBoiUpdate {
-        balls: self.balls,
-        phase: self.phase,
-      })
-    }
-  }
-
-  fn pop_event(&mut self) -> Option<ModuleEvent> {
-    self.events.pop()
+    self.ui_state.write(BouncyBoiUiState {
+      balls: self.balls,
+      phase: self.phase,
+    });
   }
 
   fn get_parameters(&mut self) -> Vec<&mut AudioParam> {
@@ -142,6 +122,10 @@ impl Module for BouncyBoi {
 
     outputs
   }
+
+  fn get_pointers(&mut self) -> Vec<usize> {
+    vec![self.ui_state.as_ptr()]
+  }
 }
 
 impl BouncyBoi {
@@ -164,8 +148,7 @@ impl BouncyBoi {
       gravity: AudioParam::default(),
 
       phase: 0.0,
-      cycle_count: 0,
-      events: vec![],
+      ui_state: UiState::new(BouncyBoiUiState::default()),
     });
     let mut rng = Rng::default();
 
@@ -0,0 +1,163 @@
+use std::f32::consts::PI;
+
+use crate::audio_output::AudioOutput;
+use crate::audio_param::{AudioParam, AudioParamModulationType};
+use crate::{
+  modulate_core::{INV_SAMPLE_RATE, QUANTUM_SIZE},
+  module::Module,
+  rng::Rng,
+};
+
+// Constants of the Rössler attractor. Its `c` is swept by the chaos amount, from a simple limit
+// cycle at the low end to the chaotic attractor at the high end.
+const ROSSLER_A: f32 = 0.2;
+const ROSSLER_B: f32 = 0.2;
+const ROSSLER_MIN_C: f32 = 2.5;
+const ROSSLER_MAX_C: f32 = 6.0;
+// Time it takes the attractor to go around once, so that the rate is roughly in Hz.
+const ROSSLER_PERIOD: f32 = 6.0;
+// The attractor is integrated with one Euler step per sample, which only stays stable while the
+// steps are small, so the rate is limited.
+const MAX_RATE: f32 = 100.0;
+// Bring the coordinates of the attractor to about [-1, 1]. The z coordinate stays close to zero,
+// with tall spikes as the attractor folds over, so it's scaled down further.
+const ROSSLER_SCALE: f32 = 0.1;
+const ROSSLER_Z_SCALE: f32 = 0.04;
+
+// Size of the steps of the random walk at no chaos, relative to the distance to the new value.
+const MIN_STEP_SIZE: f32 = 0.1;
+
+// The logistic map is periodic below r = ~3.57 and chaotic above it.
+const LOGISTIC_MIN_R: f32 = 3.5;
+const LOGISTIC_MAX_R: f32 = 4.0;
+
+// Low frequency random and chaotic modulation sources. All of them advance at the rate, and the
+// chaos amount makes them less predictable.
+pub struct Chaos {
+  smooth_output: AudioOutput,
+  stepped_output: AudioOutput,
+  x_output: AudioOutput,
+  y_output: AudioOutput,
+  z_output: AudioOutput,
+  logistic_output: AudioOutput,
+
+  rate_param: AudioParam,
+  chaos_param: AudioParam,
+
+  rng: Rng,
+  phase: f32,
+  previous_step: f32,
+  step: f32,
+
+  x: f32,
+  y: f32,
+  z: f32,
+
+  logistic: f32,
+}
+
+impl Module for Chaos {
+  fn process(&mut self, _quantum: u64) {
+    for sample in 0..QUANTUM_SIZE {
+      let rate = self.rate_param.at(sample).clamp(0.0, MAX_RATE);
+      let chaos = self.chaos_param.at(sample).clamp(0.0, 1.0);
+
+      self.phase += rate * INV_SAMPLE_RATE;
+      if self.phase >= 1.0 {
+        self.phase -= f32::floor(self.phase);
+
+        // A random walk whose steps grow with the chaos amount, becoming plain random values
+        // at full chaos.
+        let step_size = MIN_STEP_SIZE + (1.0 - MIN_STEP_SIZE) * chaos;
+        self.previous_step = self.step;
+        self.step += (self.rng.get_bipolar_f32() - self.step) * step_size;
+
+        let r = LOGISTIC_MIN_R + (LOGISTIC_MAX_R - LOGISTIC_MIN_R) * chaos;
+        self.logistic = r * self.logistic * (1.0 - self.logistic);
+        // Keep the map from getting stuck at its fixed points at 0 and 1.
+        if self.logistic <= 0.0 || self.logistic >= 1.0 {
+          self.logistic = self.rng.get_f32();
+        }
+      }
+
+      // Cosine interpolation between the last two steps, which has no corners at the steps.
+      let t = 0.5 - 0.5 * f32::cos(self.phase * PI);
+      let smooth = self.previous_step + (self.step - self.previous_step) * t;
+
+      let c = ROSSLER_MIN_C + (ROSSLER_MAX_C - ROSSLER_MIN_C) * chaos;
+      let dt = rate * ROSSLER_PERIOD * INV_SAMPLE_RATE;
+      let dx = -self.y - self.z;
+      let dy = self.x + ROSSLER_A * self.y;
+      let dz = ROSSLER_B + self.z * (self.x - c);
+      self.x += dx * dt;
+      self.y += dy * dt;
+      self.z += dz * dt;
+
+      self.smooth_output[sample] = smooth;
+      self.stepped_output[sample] = self.step;
+      self.x_output[sample] = self.x * ROSSLER_SCALE;
+      self.y_output[sample] = self.y * ROSSLER_SCALE;
+      self.z_output[sample] = self.z * ROSSLER_Z_SCALE;
+      self.logistic_output[sample] = self.logistic * 2.0 - 1.0;
+    }
+  }
+
+  fn get_parameters(&mut self) -> Vec<&mut AudioParam> {
+    vec![&mut self.rate_param, &mut self.chaos_param]
+  }
+
+  fn get_outputs(&mut self) -> Vec<&mut AudioOutput> {
+    vec![
+      &mut self.smooth_output,
+      &mut self.stepped_output,
+      &mut self.x_output,
+      &mut self.y_output,
+      &mut self.z_output,
+      &mut self.logistic_output,
+    ]
+  }
+
+  fn reset(&mut self) {
+    self.phase = 0.0;
+    self.previous_step = 0.0;
+    self.step = 0.0;
+    self.restart_attractors();
+  }
+}
+
+impl Chaos {
+  pub fn new() -> Box<Chaos> {
+    let mut chaos = Box::new(Chaos {
+      smooth_output: AudioOutput::default(),
+      stepped_output: AudioOutput::default(),
+      x_output: AudioOutput::default(),
+      y_output: AudioOutput::default(),
+      z_output: AudioOutput::default(),
+      logistic_output: AudioOutput::default(),
+
+      rate_param: AudioParam::new(AudioParamModulationType::Additive),
+      chaos_param: AudioParam::new(AudioParamModulationType::Additive),
+
+      rng: Rng::unique(),
+      phase: 0.0,
+      previous_step: 0.0,
+      step: 0.0,
+
+      x: 0.0,
+      y: 0.0,
+      z: 0.0,
+
+      logistic: 0.0,
+    });
+    chaos.restart_attractors();
+    chaos
+  }
+
+  // Start each instance from a different point, so that several of them don't move in unison.
+  fn restart_attractors(&mut self) {
+    self.x = 1.0 + self.rng.get_bipolar_f32();
+    self.y = 0.0;
+    self.z = 0.0;
+    self.logistic = self.rng.get_f32();
+  }
+}
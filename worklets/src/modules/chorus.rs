@@ -107,6 +107,11 @@ impl Module for Chorus {
   fn get_inputs(&mut self) -> Vec<&mut AudioInput> {
     vec![&mut self.input]
   }
+
+  fn reset(&mut self) {
+    self.buffers = [[0.0; CHORUS_BUFFER_LENGTH]; 4];
+    self.modulation = 0.0;
+  }
 }
 
 impl Chorus {
@@ -47,6 +47,10 @@ impl Module for Delay {
       &mut self.dry,
     ]
   }
+
+  fn reset(&mut self) {
+    self.delay.reset();
+  }
 }
 
 impl Delay {
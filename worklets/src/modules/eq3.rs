@@ -74,6 +74,12 @@ impl Module for EQ3 {
   fn get_outputs(&mut self) -> Vec<&mut AudioOutput> {
     vec![&mut self.output]
   }
+
+  fn reset(&mut self) {
+    self.lowself.reset();
+    self.highself.reset();
+    self.peaking.reset();
+  }
 }
 
 impl EQ3 {
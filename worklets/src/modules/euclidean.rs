@@ -0,0 +1,176 @@
+use crate::audio_input::AudioInput;
+use crate::audio_output::AudioOutput;
+use crate::audio_param::AudioParam;
+use crate::{edge_detector::EdgeDetector, modulate_core::QUANTUM_SIZE, module::Module};
+
+const NUM_CHANNELS: usize = 4;
+const MAX_STEPS: usize = 32;
+
+#[derive(Default)]
+struct Channel {
+  steps_param: AudioParam,
+  pulses_param: AudioParam,
+  rotation_param: AudioParam,
+
+  position: usize,
+  is_pulse: bool,
+}
+
+// Spreads the pulses as evenly as possible over the steps, which gives the same patterns as
+// Bjorklund's algorithm up to a rotation. Computed per step, so the parameters can change at any
+// time without rebuilding the pattern.
+fn is_pulse(step: usize, steps: usize, pulses: usize, rotation: usize) -> bool {
+  ((step + rotation) % steps) * pulses % steps < pulses
+}
+
+// Several Euclidean rhythms driven by the same clock. The gates follow the clock on the steps
+// with a pulse.
+pub struct Euclidean {
+  clock_input: AudioInput,
+  reset_input: AudioInput,
+  gate_outputs: [AudioOutput; NUM_CHANNELS],
+
+  channels: [Channel; NUM_CHANNELS],
+
+  clock_edge_detector: EdgeDetector,
+  reset_edge_detector: EdgeDetector,
+  // After a reset the next clock edge plays the first step instead of advancing.
+  is_reset: bool,
+}
+
+impl Module for Euclidean {
+  fn process(&mut self, _quantum: u64) {
+    for sample in 0..QUANTUM_SIZE {
+      if self
+        .reset_edge_detector
+        .step(self.reset_input.at(sample))
+        .rose()
+      {
+        self.is_reset = true;
+      }
+
+      let clock = self.clock_edge_detector.step(self.clock_input.at(sample));
+
+      if clock.rose() {
+        for channel in self.channels.iter_mut() {
+          let steps = (channel.steps_param.at(sample).round() as usize).clamp(1, MAX_STEPS);
+          let pulses = (channel.pulses_param.at(sample).round().max(0.0) as usize).min(steps);
+          let rotation = channel.rotation_param.at(sample).round().max(0.0) as usize % steps;
+
+          channel.position = if self.is_reset {
+            0
+          } else {
+            (channel.position + 1) % steps
+          };
+          channel.is_pulse = is_pulse(channel.position, steps, pulses, rotation);
+        }
+
+        self.is_reset = false;
+      }
+
+      for (channel, output) in self.channels.iter().zip(self.gate_outputs.iter_mut()) {
+        output[sample] = if channel.is_pulse && clock.is_high() {
+          1.0
+        } else {
+          0.0
+        };
+      }
+    }
+  }
+
+  fn get_inputs(&mut self) -> Vec<&mut AudioInput> {
+    vec![&mut self.clock_input, &mut self.reset_input]
+  }
+
+  fn get_parameters(&mut self) -> Vec<&mut AudioParam> {
+    self
+      .channels
+      .iter_mut()
+      .flat_map(|channel| {
+        [
+          &mut channel.steps_param,
+          &mut channel.pulses_param,
+          &mut channel.rotation_param,
+        ]
+      })
+      .collect()
+  }
+
+  fn get_outputs(&mut self) -> Vec<&mut AudioOutput> {
+    self.gate_outputs.iter_mut().collect()
+  }
+
+  fn reset(&mut self) {
+    for channel in self.channels.iter_mut() {
+      channel.position = 0;
+      channel.is_pulse = false;
+    }
+    self.is_reset = true;
+  }
+}
+
+impl Euclidean {
+  pub fn new() -> Box<Euclidean> {
+    Box::new(Euclidean {
+      clock_input: AudioInput::default(),
+      reset_input: AudioInput::default(),
+      gate_outputs: Default::default(),
+
+      channels: Default::default(),
+
+      clock_edge_detector: EdgeDetector::default(),
+      reset_edge_detector: EdgeDetector::default(),
+      is_reset: true,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn pattern(steps: usize, pulses: usize, rotation: usize) -> String {
+    (0..steps)
+      .map(|step| {
+        if is_pulse(step, steps, pulses, rotation) {
+          'x'
+        } else {
+          '.'
+        }
+      })
+      .collect()
+  }
+
+  #[test]
+  fn matches_bjorklund_patterns() {
+    assert_eq!(pattern(8, 3, 0), "x..x..x.");
+    assert_eq!(pattern(5, 2, 0), "x..x.");
+    assert_eq!(pattern(16, 5, 0), "x...x..x..x..x..");
+  }
+
+  #[test]
+  fn rotation_shifts_the_pattern() {
+    assert_eq!(pattern(8, 3, 1), "..x..x.x");
+    assert_eq!(pattern(8, 3, 8 - 1), ".x..x..x");
+  }
+
+  #[test]
+  fn spreads_pulses_evenly() {
+    for steps in 1..=MAX_STEPS {
+      for pulses in 0..=steps {
+        let onsets: Vec<usize> = (0..steps)
+          .filter(|step| is_pulse(*step, steps, pulses, 0))
+          .collect();
+        assert_eq!(onsets.len(), pulses, "E({}, {})", pulses, steps);
+
+        // The gaps between the pulses, wrapping around, differ by at most one step.
+        let gaps: Vec<usize> = (0..onsets.len())
+          .map(|i| (onsets[(i + 1) % onsets.len()] + steps - onsets[i] - 1) % steps + 1)
+          .collect();
+        if let (Some(min), Some(max)) = (gaps.iter().min(), gaps.iter().max()) {
+          assert!(max - min <= 1, "E({}, {}): {:?}", pulses, steps, gaps);
+        }
+      }
+    }
+  }
+}
@@ -27,6 +27,12 @@ impl Diffuser {
     }
   }
 
+  fn reset(&mut self) {
+    for allpass in self.allpasses.iter_mut() {
+      allpass.reset();
+    }
+  }
+
   fn step(&mut self, input: f32) -> f32 {
     let mut output = input;
 
@@ -145,6 +151,14 @@ impl Module for FDNReverb {
   fn get_outputs(&mut self) -> Vec<&mut AudioOutput> {
     vec![&mut self.output]
   }
+
+  fn reset(&mut self) {
+    for delay in self.delays.iter_mut() {
+      delay.reset();
+    }
+    self.diffuser.reset();
+    self.modulation = 0.0;
+  }
 }
 
 impl FDNReverb {
@@ -0,0 +1,224 @@
+use std::f32::consts::TAU;
+
+use crate::audio_input::AudioInput;
+use crate::audio_output::AudioOutput;
+use crate::audio_param::{AudioParam, AudioParamModulationType};
+use crate::{
+  adsr_curve::ADSRCurve,
+  modulate_core::{INV_SAMPLE_RATE, QUANTUM_SIZE},
+  module::Module,
+  tuning::voltage_to_freq,
+};
+
+const NUM_OPERATORS: usize = 4;
+
+// Phase offset in cycles caused by a modulator at full level.
+const MODULATION_DEPTH: f32 = 2.0;
+
+// The algorithms of the classic four operator synths. For each operator there's a mask of the
+// operators modulating it, and each algorithm has a mask of the operators heard in the output.
+// Operators are processed from operator 4 to operator 1, and are only ever modulated by operators
+// with a higher number.
+struct Algorithm {
+  modulators: [u8; NUM_OPERATORS],
+  carriers: u8,
+}
+
+const OP1: u8 = 1 << 0;
+const OP2: u8 = 1 << 1;
+const OP3: u8 = 1 << 2;
+const OP4: u8 = 1 << 3;
+
+#[rustfmt::skip]
+const ALGORITHMS: [Algorithm; 8] = [
+  // 4 -> 3 -> 2 -> 1
+  Algorithm { modulators: [OP2, OP3, OP4, 0], carriers: OP1 },
+  // (3 + 4) -> 2 -> 1
+  Algorithm { modulators: [OP2, OP3 | OP4, 0, 0], carriers: OP1 },
+  // (3 -> 2 + 4) -> 1
+  Algorithm { modulators: [OP2 | OP4, OP3, 0, 0], carriers: OP1 },
+  // (4 -> 3 + 2) -> 1
+  Algorithm { modulators: [OP2 | OP3, 0, OP4, 0], carriers: OP1 },
+  // 2 -> 1, 4 -> 3
+  Algorithm { modulators: [OP2, 0, OP4, 0], carriers: OP1 | OP3 },
+  // 4 -> (1, 2, 3)
+  Algorithm { modulators: [OP4, OP4, OP4, 0], carriers: OP1 | OP2 | OP3 },
+  // 4 -> 3, 1, 2
+  Algorithm { modulators: [0, 0, OP4, 0], carriers: OP1 | OP2 | OP3 },
+  // 1, 2, 3, 4
+  Algorithm { modulators: [0, 0, 0, 0], carriers: OP1 | OP2 | OP3 | OP4 },
+];
+
+struct Operator {
+  ratio: AudioParam,
+  detune: AudioParam,
+  level: AudioParam,
+  attack_time: AudioParam,
+  decay_time: AudioParam,
+  sustain_level: AudioParam,
+  release_time: AudioParam,
+
+  envelope: ADSRCurve,
+  phase: f32,
+}
+
+impl Operator {
+  fn new() -> Operator {
+    Operator {
+      ratio: AudioParam::new(AudioParamModulationType::Additive),
+      detune: AudioParam::new(AudioParamModulationType::Additive),
+      level: AudioParam::new(AudioParamModulationType::Additive),
+      attack_time: AudioParam::new(AudioParamModulationType::Additive),
+      decay_time: AudioParam::new(AudioParamModulationType::Additive),
+      sustain_level: AudioParam::new(AudioParamModulationType::Additive),
+      release_time: AudioParam::new(AudioParamModulationType::Additive),
+
+      envelope: ADSRCurve::default(),
+      phase: 0.0,
+    }
+  }
+
+  fn get_parameters(&mut self) -> [&mut AudioParam; 7] {
+    [
+      &mut self.ratio,
+      &mut self.detune,
+      &mut self.level,
+      &mut self.attack_time,
+      &mut self.decay_time,
+      &mut self.sustain_level,
+      &mut self.release_time,
+    ]
+  }
+}
+
+pub struct FMVoice {
+  gate_input: AudioInput,
+  output: AudioOutput,
+
+  cv_param: AudioParam,
+  algorithm_param: AudioParam,
+  feedback_param: AudioParam,
+  level: AudioParam,
+
+  // Stored from operator 1 to operator 4.
+  operators: [Operator; NUM_OPERATORS],
+  // The last two outputs of operator 4, averaged for the feedback to keep it from turning into
+  // noise at high amounts.
+  feedback_history: [f32; 2],
+}
+
+impl Module for FMVoice {
+  fn process(&mut self, _quantum: u64) {
+    // Detuning is updated once per quantum, which spares a `powf` per operator and sample.
+    let mut detune_ratios = [1.0; NUM_OPERATORS];
+    for (operator, detune_ratio) in self.operators.iter_mut().zip(detune_ratios.iter_mut()) {
+      *detune_ratio = f32::powf(2.0, operator.detune.at(0) / 12.0);
+    }
+
+    for sample in 0..QUANTUM_SIZE {
+      let algorithm_index = self.algorithm_param.at(sample).round() as usize;
+      let algorithm = &ALGORITHMS[algorithm_index.min(ALGORITHMS.len() - 1)];
+
+      let gate = self.gate_input.at(sample);
+      let freq = voltage_to_freq(self.cv_param.at(sample));
+      let feedback = self.feedback_param.at(sample);
+
+      let mut operator_outputs = [0.0; NUM_OPERATORS];
+      let mut output = 0.0;
+
+      for index in (0..NUM_OPERATORS).rev() {
+        let operator = &mut self.operators[index];
+
+        operator.envelope.attack_time = operator.attack_time.at(sample);
+        operator.envelope.decay_time = operator.decay_time.at(sample);
+        operator.envelope.sustain_level = operator.sustain_level.at(sample);
+        operator.envelope.release_time = operator.release_time.at(sample);
+        let envelope = operator.envelope.step(gate);
+
+        let mut modulation = 0.0;
+        for (modulator, modulator_output) in operator_outputs.iter().enumerate() {
+          if algorithm.modulators[index] & (1 << modulator) != 0 {
+            modulation += modulator_output;
+          }
+        }
+        if index == NUM_OPERATORS - 1 {
+          modulation += feedback * (self.feedback_history[0] + self.feedback_history[1]) * 0.5;
+        }
+
+        let mut phase = operator.phase + modulation * MODULATION_DEPTH;
+        phase -= f32::floor(phase);
+
+        let value = f32::sin(phase * TAU) * operator.level.at(sample) * envelope;
+        operator_outputs[index] = value;
+
+        if algorithm.carriers & (1 << index) != 0 {
+          output += value;
+        }
+
+        let operator_freq = freq * operator.ratio.at(sample) * detune_ratios[index];
+        operator.phase += operator_freq * INV_SAMPLE_RATE;
+        operator.phase -= f32::floor(operator.phase);
+      }
+
+      self.feedback_history = [
+        operator_outputs[NUM_OPERATORS - 1],
+        self.feedback_history[0],
+      ];
+
+      self.output[sample] = output * self.level.at(sample);
+    }
+  }
+
+  fn get_inputs(&mut self) -> Vec<&mut AudioInput> {
+    vec![&mut self.gate_input]
+  }
+
+  fn get_parameters(&mut self) -> Vec<&mut AudioParam> {
+    let mut parameters = vec![
+      &mut self.cv_param,
+      &mut self.algorithm_param,
+      &mut self.feedback_param,
+      &mut self.level,
+    ];
+
+    for operator in self.operators.iter_mut() {
+      parameters.extend(operator.get_parameters());
+    }
+
+    parameters
+  }
+
+  fn get_outputs(&mut self) -> Vec<&mut AudioOutput> {
+    vec![&mut self.output]
+  }
+
+  fn reset(&mut self) {
+    for operator in self.operators.iter_mut() {
+      operator.envelope = ADSRCurve::default();
+      operator.phase = 0.0;
+    }
+    self.feedback_history = [0.0; 2];
+  }
+}
+
+impl FMVoice {
+  pub fn new() -> Box<FMVoice> {
+    Box::new(FMVoice {
+      gate_input: AudioInput::default(),
+      output: AudioOutput::default(),
+
+      cv_param: AudioParam::new(AudioParamModulationType::Additive),
+      algorithm_param: AudioParam::new(AudioParamModulationType::Additive),
+      feedback_param: AudioParam::new(AudioParamModulationType::Additive),
+      level: AudioParam::new(AudioParamModulationType::Additive),
+
+      operators: [
+        Operator::new(),
+        Operator::new(),
+        Operator::new(),
+        Operator::new(),
+      ],
+      feedback_history: [0.0; 2],
+    })
+  }
+}
@@ -0,0 +1,212 @@
+use crate::audio_input::AudioInput;
+use crate::audio_output::AudioOutput;
+use crate::audio_param::{AudioParam, AudioParamModulationType};
+use crate::{
+  edge_detector::EdgeDetector,
+  modulate_core::{QUANTUM_SIZE, SAMPLE_RATE},
+  module::{GateStep, Module, ModuleMessage},
+  rng::Rng,
+  ui_state::UiState,
+};
+
+const NUM_TRACKS: usize = 8;
+const MAX_STEPS: usize = 64;
+const MAX_RATCHETS: u32 = 8;
+
+// Clock period assumed until two clock edges have been seen, 16th notes at 120 BPM.
+const DEFAULT_CLOCK_PERIOD: usize = SAMPLE_RATE / 8;
+
+const EMPTY_STEP: GateStep = GateStep {
+  gate: false,
+  velocity: 1.0,
+  probability: 1.0,
+  ratchets: 1,
+};
+
+#[derive(Clone, Copy)]
+struct Track {
+  steps: [GateStep; MAX_STEPS],
+  length: usize,
+  position: usize,
+
+  // The step being played, after rolling its probability.
+  is_playing: bool,
+  velocity: f32,
+  ratchets: u32,
+}
+
+impl Default for Track {
+  fn default() -> Track {
+    Track {
+      steps: [EMPTY_STEP; MAX_STEPS],
+      length: 16,
+      position: 0,
+
+      is_playing: false,
+      velocity: 0.0,
+      ratchets: 1,
+    }
+  }
+}
+
+// Drum style sequencer with a gate per track. Every track has its own length, so tracks of
+// different lengths drift against each other. Each gate is as high as the velocity of its step.
+pub struct GateSequencer {
+  clock_input: AudioInput,
+  reset_input: AudioInput,
+  gate_outputs: [AudioOutput; NUM_TRACKS],
+
+  gate_length_param: AudioParam,
+
+  clock_edge_detector: EdgeDetector,
+  reset_edge_detector: EdgeDetector,
+
+  tracks: [Track; NUM_TRACKS],
+  // Positions of the tracks read by the UI.
+  playheads: UiState<[f32; NUM_TRACKS]>,
+  // After a reset the next clock edge plays the first step instead of advancing.
+  is_reset: bool,
+
+  rng: Rng,
+
+  // Samples since the last clock edge, and between the last two, which the gate length and the
+  // ratchets are relative to.
+  time: usize,
+  clock_period: usize,
+}
+
+impl Module for GateSequencer {
+  fn process(&mut self, _quantum: u64) {
+    for sample in 0..QUANTUM_SIZE {
+      if self
+        .reset_edge_detector
+        .step(self.reset_input.at(sample))
+        .rose()
+      {
+        self.is_reset = true;
+      }
+
+      if self
+        .clock_edge_detector
+        .step(self.clock_input.at(sample))
+        .rose()
+      {
+        if self.time > 0 {
+          self.clock_period = self.time;
+        }
+        self.time = 0;
+        self.advance();
+      }
+
+      let gate_length = self.gate_length_param.at(sample).clamp(0.0, 1.0);
+
+      for (track, output) in self.tracks.iter().zip(self.gate_outputs.iter_mut()) {
+        // Ratchets split the step into equally long repeats.
+        let ratchet_period = (self.clock_period / track.ratchets as usize).max(1);
+        let ratchet = self.time / ratchet_period;
+        let ratchet_time = self.time % ratchet_period;
+
+        let is_gate_open = track.is_playing
+          && ratchet < track.ratchets as usize
+          && (ratchet_time as f32) < gate_length * ratchet_period as f32;
+
+        output[sample] = if is_gate_open { track.velocity } else { 0.0 };
+      }
+
+      self.time += 1;
+    }
+
+    let mut playheads = [0.0; NUM_TRACKS];
+    for (playhead, track) in playheads.iter_mut().zip(self.tracks.iter()) {
+      *playhead = track.position as f32;
+    }
+    self.playheads.write(playheads);
+  }
+
+  fn on_message(&mut self, message: ModuleMessage) {
+    if let ModuleMessage::GateSequencerSetTrack {
+      track,
+      length,
+      steps,
+    } = message
+    {
+      let Some(track) = self.tracks.get_mut(track) else {
+        return;
+      };
+
+      track.length = length.clamp(1, MAX_STEPS);
+      for (step, new_step) in track.steps.iter_mut().zip(steps.iter()) {
+        *step = *new_step;
+      }
+    }
+  }
+
+  fn get_inputs(&mut self) -> Vec<&mut AudioInput> {
+    vec![&mut self.clock_input, &mut self.reset_input]
+  }
+
+  fn get_parameters(&mut self) -> Vec<&mut AudioParam> {
+    vec![&mut self.gate_length_param]
+  }
+
+  fn get_outputs(&mut self) -> Vec<&mut AudioOutput> {
+    self.gate_outputs.iter_mut().collect()
+  }
+
+  fn get_pointers(&mut self) -> Vec<usize> {
+    vec![self.playheads.as_ptr()]
+  }
+
+  fn reset(&mut self) {
+    for track in self.tracks.iter_mut() {
+      track.position = 0;
+      track.is_playing = false;
+    }
+    self.is_reset = true;
+    self.time = 0;
+    self.clock_period = DEFAULT_CLOCK_PERIOD;
+  }
+}
+
+impl GateSequencer {
+  pub fn new() -> Box<GateSequencer> {
+    Box::new(GateSequencer {
+      clock_input: AudioInput::default(),
+      reset_input: AudioInput::default(),
+      gate_outputs: Default::default(),
+
+      gate_length_param: AudioParam::new(AudioParamModulationType::Additive),
+
+      clock_edge_detector: EdgeDetector::default(),
+      reset_edge_detector: EdgeDetector::default(),
+
+      tracks: [Track::default(); NUM_TRACKS],
+      playheads: UiState::new([0.0; NUM_TRACKS]),
+      is_reset: true,
+
+      rng: Rng::unique(),
+
+      time: 0,
+      clock_period: DEFAULT_CLOCK_PERIOD,
+    })
+  }
+
+  fn advance(&mut self) {
+    let is_reset = self.is_reset;
+    self.is_reset = false;
+
+    for track in self.tracks.iter_mut() {
+      track.position = if is_reset {
+        0
+      } else {
+        (track.position + 1) % track.length
+      };
+
+      // The probability is rolled once per step, so a step plays all of its ratchets or none.
+      let step = &track.steps[track.position];
+      track.is_playing = step.gate && self.rng.get_f32() < step.probability;
+      track.velocity = step.velocity.clamp(0.0, 1.0);
+      track.ratchets = step.ratchets.clamp(1, MAX_RATCHETS);
+    }
+  }
+}
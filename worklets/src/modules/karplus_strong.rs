@@ -0,0 +1,184 @@
+use crate::audio_input::AudioInput;
+use crate::audio_output::AudioOutput;
+use crate::audio_param::{AudioParam, AudioParamModulationType};
+use crate::{
+  delay_line::VariableDelayLine,
+  edge_detector::EdgeDetector,
+  filters::allpass_filter::AllpassFilter,
+  modulate_core::{QUANTUM_SIZE, SAMPLE_RATE_F32},
+  module::Module,
+  rng::Rng,
+  tuning::voltage_to_freq,
+};
+
+// Enough for the round trip of a string tuned down to ~11 Hz.
+const DELAY_LINE_SIZE: usize = 4096;
+
+// The fractional delay left for the allpass filter is kept within [0.1, 1.1) samples, where its
+// phase delay is close to constant over most of the spectrum.
+const MIN_ALLPASS_DELAY: f32 = 0.1;
+
+// Bow friction as in the bowed string model of the Synthesis ToolKit.
+const BOW_PRESSURE_SLOPE: f32 = 3.0;
+const MIN_BOW_VELOCITY: f32 = 0.03;
+const MAX_BOW_VELOCITY: f32 = 0.23;
+
+fn bow_friction(velocity: f32) -> f32 {
+  let x = (velocity * BOW_PRESSURE_SLOPE).abs() + 0.75;
+  f32::min(x.powi(-4), 1.0)
+}
+
+// A string modelled as two digital waveguides meeting at the point where the string is plucked or
+// bowed. One of them holds the round trip to the nut and the other the round trip to the bridge,
+// where the losses of the string and the fine tuning are applied.
+pub struct KarplusStrong {
+  trigger_input: AudioInput,
+  exciter_input: AudioInput,
+  output: AudioOutput,
+
+  cv_param: AudioParam,
+  damping_param: AudioParam,
+  brightness_param: AudioParam,
+  position_param: AudioParam,
+  decay_param: AudioParam,
+  bow_param: AudioParam,
+  level: AudioParam,
+
+  trigger_edge_detector: EdgeDetector,
+  neck_delay: VariableDelayLine,
+  bridge_delay: VariableDelayLine,
+  tuning_allpass: AllpassFilter,
+  previous_bridge_sample: f32,
+
+  rng: Rng,
+  // Remaining length of the noise burst of the latest pluck, and the state of its lowpass filter.
+  burst_remaining: usize,
+  burst_filter: f32,
+}
+
+impl Module for KarplusStrong {
+  fn process(&mut self, _quantum: u64) {
+    for sample in 0..QUANTUM_SIZE {
+      let cv = self.cv_param.at(sample);
+      let damping = self.damping_param.at(sample).clamp(0.0, 1.0);
+      let brightness = self.brightness_param.at(sample).clamp(0.0, 1.0);
+      let position = self.position_param.at(sample).clamp(0.02, 0.5);
+      let decay = self.decay_param.at(sample).max(0.01);
+      let bow = self.bow_param.at(sample).clamp(0.0, 1.0);
+
+      let freq = f32::min(voltage_to_freq(cv), SAMPLE_RATE_F32 / 8.0);
+      let period = SAMPLE_RATE_F32 / freq;
+
+      // The loss filter averages two consecutive samples, delaying the signal by `loss` samples.
+      let loss = damping * 0.5;
+      let remaining = period - loss;
+      let delay = f32::max(f32::floor(remaining - MIN_ALLPASS_DELAY), 2.0);
+      let fraction = remaining - delay;
+      self
+        .tuning_allpass
+        .set_gain((1.0 - fraction) / (1.0 + fraction));
+
+      let delay = usize::min(delay as usize, DELAY_LINE_SIZE - 1);
+      let bridge_length = usize::max((delay as f32 * position).round() as usize, 1);
+      self.bridge_delay.set_delay(bridge_length);
+      self
+        .neck_delay
+        .set_delay(usize::max(delay - bridge_length, 1));
+
+      // Gain of one trip around the string, so that it decays by 60 dB in `decay` seconds.
+      let loop_gain = f32::powf(0.001, 1.0 / (decay * freq));
+
+      let trigger = self
+        .trigger_edge_detector
+        .step(self.trigger_input.at(sample));
+      if trigger.rose() {
+        self.burst_remaining = period as usize;
+      }
+
+      let bridge_sample = self.bridge_delay.read();
+      let lowpassed = (1.0 - loss) * bridge_sample + loss * self.previous_bridge_sample;
+      self.previous_bridge_sample = bridge_sample;
+      let bridge_reflection = -self.tuning_allpass.step(lowpassed) * loop_gain;
+      let nut_reflection = -self.neck_delay.read();
+
+      let mut excitation = self.exciter_input.at(sample);
+
+      if self.burst_remaining > 0 {
+        self.burst_remaining -= 1;
+        let noise = self.rng.get_bipolar_f32();
+        let cutoff = 0.05 + 0.95 * brightness;
+        self.burst_filter += (noise - self.burst_filter) * cutoff;
+        excitation += self.burst_filter;
+      }
+
+      if bow > 0.0 && trigger.is_high() {
+        let string_velocity = bridge_reflection + nut_reflection;
+        let bow_velocity = MIN_BOW_VELOCITY + (MAX_BOW_VELOCITY - MIN_BOW_VELOCITY) * bow;
+        let velocity_difference = bow_velocity - string_velocity;
+        excitation += velocity_difference * bow_friction(velocity_difference);
+      }
+
+      self.neck_delay.write(bridge_reflection + excitation);
+      self.bridge_delay.write(nut_reflection + excitation);
+
+      self.output[sample] = bridge_reflection * self.level.at(sample);
+    }
+  }
+
+  fn get_inputs(&mut self) -> Vec<&mut AudioInput> {
+    vec![&mut self.trigger_input, &mut self.exciter_input]
+  }
+
+  fn get_parameters(&mut self) -> Vec<&mut AudioParam> {
+    vec![
+      &mut self.cv_param,
+      &mut self.damping_param,
+      &mut self.brightness_param,
+      &mut self.position_param,
+      &mut self.decay_param,
+      &mut self.bow_param,
+      &mut self.level,
+    ]
+  }
+
+  fn get_outputs(&mut self) -> Vec<&mut AudioOutput> {
+    vec![&mut self.output]
+  }
+
+  fn reset(&mut self) {
+    self.neck_delay.reset();
+    self.bridge_delay.reset();
+    self.tuning_allpass.reset();
+    self.previous_bridge_sample = 0.0;
+    self.burst_remaining = 0;
+    self.burst_filter = 0.0;
+  }
+}
+
+impl KarplusStrong {
+  pub fn new() -> Box<KarplusStrong> {
+    Box::new(KarplusStrong {
+      trigger_input: AudioInput::default(),
+      exciter_input: AudioInput::default(),
+      output: AudioOutput::default(),
+
+      cv_param: AudioParam::new(AudioParamModulationType::Additive),
+      damping_param: AudioParam::new(AudioParamModulationType::Additive),
+      brightness_param: AudioParam::new(AudioParamModulationType::Additive),
+      position_param: AudioParam::new(AudioParamModulationType::Additive),
+      decay_param: AudioParam::new(AudioParamModulationType::Additive),
+      bow_param: AudioParam::new(AudioParamModulationType::Additive),
+      level: AudioParam::new(AudioParamModulationType::Additive),
+
+      trigger_edge_detector: EdgeDetector::default(),
+      neck_delay: VariableDelayLine::new(DELAY_LINE_SIZE, 1),
+      bridge_delay: VariableDelayLine::new(DELAY_LINE_SIZE, 1),
+      tuning_allpass: AllpassFilter::new(1, 0.0),
+      previous_bridge_sample: 0.0,
+
+      rng: Rng::unique(),
+      burst_remaining: 0,
+      burst_filter: 0.0,
+    })
+  }
+}
@@ -75,6 +75,10 @@ impl Module for LFO {
       &mut self.sqr_output,
     ]
   }
+
+  fn reset(&mut self) {
+    self.phase = 0.0;
+  }
 }
 
 impl LFO {
@@ -38,6 +38,10 @@ impl Module for Limiter {
   fn get_outputs(&mut self) -> Vec<&mut AudioOutput> {
     vec![&mut self.output]
   }
+
+  fn reset(&mut self) {
+    self.buffer.reset();
+  }
 }
 
 impl Limiter {
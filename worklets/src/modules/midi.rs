@@ -2,6 +2,7 @@ use crate::audio_output::AudioOutput;
 use crate::{
   modulate_core::QUANTUM_SIZE,
   module::{Module, ModuleMessage},
+  tuning::Tuning,
 };
 
 const MIDI_NOTE_OFF: u32 = 0b1000;
@@ -20,17 +21,25 @@ pub struct MIDI {
   current_cv: f32,
 
   note_velocities: [u8; 128],
+
+  tuning: *const Tuning,
 }
 
 impl Module for MIDI {
   fn process(&mut self, _quantum: u64) {
+    let tuning = unsafe { &*self.tuning };
     let mut velocity = 0.0;
 
     for note in 0..128 {
       let index = 127 - note;
 
       if self.note_velocities[index] > 0 {
-        self.current_cv = ((index as f32) - 57.0) / 12.0;
+        // Notes left unmapped by the tuning aren't played.
+        let Some(voltage) = tuning.note_to_voltage(index as i32) else {
+          continue;
+        };
+
+        self.current_cv = voltage;
         velocity = (self.note_velocities[index] as f32) / 128.0;
         break;
       }
@@ -78,7 +87,7 @@ impl Module for MIDI {
   }
 }
 impl MIDI {
-  pub fn new() -> Box<MIDI> {
+  pub fn new(tuning: &Tuning) -> Box<MIDI> {
     Box::new(MIDI {
       cv_output: AudioOutput::default(),
       velocity_output: AudioOutput::default(),
@@ -87,6 +96,8 @@ impl MIDI {
       current_cv: 0.0,
 
       note_velocities: [0; 128],
+
+      tuning,
     })
   }
 }
@@ -1,23 +1,35 @@
 pub mod adsr;
+pub mod arpeggiator;
 pub mod audio_out;
 pub mod biquad_filter;
 pub mod bouncy_boi;
+pub mod chaos;
 pub mod chorus;
 pub mod clock;
 pub mod delay;
 pub mod eq3;
+pub mod euclidean;
 pub mod fdn_reverb;
+pub mod fm_voice;
 pub mod gain;
+pub mod gate_sequencer;
+pub mod karplus_strong;
 pub mod lfo;
 pub mod limiter;
 pub mod midi;
 pub mod mixer;
+pub mod modal_resonator;
+pub mod noise;
 pub mod oscillator;
 pub mod oscilloscope;
 pub mod piano_roll;
 pub mod pow_shaper;
+pub mod quantizer;
 pub mod ring_mod;
+pub mod sample_and_hold;
 pub mod sampler;
 pub mod sequencer;
 pub mod sideq;
+pub mod turing_machine;
 pub mod virtual_controller;
+pub mod wavetable;
@@ -0,0 +1,168 @@
+use std::f32::consts::{PI, TAU};
+
+use crate::audio_input::AudioInput;
+use crate::audio_output::AudioOutput;
+use crate::audio_param::{AudioParam, AudioParamModulationType};
+use crate::{
+  filters::biquad_filter::BiquadFilter,
+  modulate_core::{INV_SAMPLE_RATE, QUANTUM_SIZE},
+  module::Module,
+  tuning::voltage_to_freq,
+};
+
+const NUM_MODES: usize = 16;
+
+// Frequencies of the modes of each structure relative to the fundamental.
+#[rustfmt::skip]
+const STRUCTURES: [[f32; NUM_MODES]; 4] = [
+  // String, harmonic.
+  [
+    1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0,
+    9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0,
+  ],
+  // Bar with free ends.
+  [
+    1.0, 2.756, 5.404, 8.933, 13.345, 18.638, 24.812, 31.868,
+    39.806, 48.624, 58.325, 68.907, 80.371, 92.717, 105.945, 120.054,
+  ],
+  // Bell.
+  [
+    0.5, 1.0, 1.183, 1.506, 2.0, 2.514, 2.662, 3.011,
+    4.166, 5.433, 6.796, 8.215, 9.672, 11.154, 12.652, 14.16,
+  ],
+  // Square plate with supported edges.
+  [
+    1.0, 2.5, 4.0, 5.0, 6.5, 8.5, 9.0, 10.0,
+    12.5, 13.0, 14.5, 16.0, 17.0, 18.5, 20.0, 20.5,
+  ],
+];
+
+// Decay time of the fundamental at no damping and at full damping, in seconds.
+const MAX_DECAY_TIME: f32 = 10.0;
+const MIN_DECAY_TIME: f32 = 0.05;
+
+pub struct ModalResonator {
+  input: AudioInput,
+  output: AudioOutput,
+
+  cv_param: AudioParam,
+  structure_param: AudioParam,
+  brightness_param: AudioParam,
+  damping_param: AudioParam,
+  position_param: AudioParam,
+  level: AudioParam,
+
+  modes: [BiquadFilter; NUM_MODES],
+  mode_gains: [f32; NUM_MODES],
+}
+
+impl Module for ModalResonator {
+  fn process(&mut self, _quantum: u64) {
+    // The modes are tuned once per quantum, as there are too many of them to do it per sample.
+    self.tune_modes();
+
+    for sample in 0..QUANTUM_SIZE {
+      let input = self.input.at(sample);
+
+      let mut output = 0.0;
+      for (mode, gain) in self.modes.iter_mut().zip(self.mode_gains.iter()) {
+        if *gain != 0.0 {
+          output += mode.step(input) * gain;
+        }
+      }
+
+      self.output[sample] = output * self.level.at(sample);
+    }
+  }
+
+  fn get_inputs(&mut self) -> Vec<&mut AudioInput> {
+    vec![&mut self.input]
+  }
+
+  fn get_parameters(&mut self) -> Vec<&mut AudioParam> {
+    vec![
+      &mut self.cv_param,
+      &mut self.structure_param,
+      &mut self.brightness_param,
+      &mut self.damping_param,
+      &mut self.position_param,
+      &mut self.level,
+    ]
+  }
+
+  fn get_outputs(&mut self) -> Vec<&mut AudioOutput> {
+    vec![&mut self.output]
+  }
+
+  fn reset(&mut self) {
+    for mode in self.modes.iter_mut() {
+      mode.reset();
+    }
+  }
+}
+
+impl ModalResonator {
+  pub fn new() -> Box<ModalResonator> {
+    Box::new(ModalResonator {
+      input: AudioInput::default(),
+      output: AudioOutput::default(),
+
+      cv_param: AudioParam::new(AudioParamModulationType::Additive),
+      structure_param: AudioParam::new(AudioParamModulationType::Additive),
+      brightness_param: AudioParam::new(AudioParamModulationType::Additive),
+      damping_param: AudioParam::new(AudioParamModulationType::Additive),
+      position_param: AudioParam::new(AudioParamModulationType::Additive),
+      level: AudioParam::new(AudioParamModulationType::Additive),
+
+      modes: Default::default(),
+      mode_gains: [0.0; NUM_MODES],
+    })
+  }
+
+  fn tune_modes(&mut self) {
+    let structure_index = self.structure_param.at(0).round() as usize;
+    let ratios = &STRUCTURES[structure_index.min(STRUCTURES.len() - 1)];
+
+    let freq = voltage_to_freq(self.cv_param.at(0));
+    let brightness = self.brightness_param.at(0).clamp(0.0, 1.0);
+    let damping = self.damping_param.at(0).clamp(0.0, 1.0);
+    let position = self.position_param.at(0).clamp(0.0, 1.0);
+
+    let decay_time = MAX_DECAY_TIME * f32::powf(MIN_DECAY_TIME / MAX_DECAY_TIME, damping);
+
+    for (index, (mode, gain)) in self
+      .modes
+      .iter_mut()
+      .zip(self.mode_gains.iter_mut())
+      .enumerate()
+    {
+      let ratio = ratios[index];
+      let mode_freq = freq * ratio;
+
+      if mode_freq >= 0.45 / INV_SAMPLE_RATE {
+        *gain = 0.0;
+        continue;
+      }
+
+      // Higher modes lose their energy faster, and are quieter the darker the tone.
+      let mode_decay_time = decay_time / f32::sqrt(ratio);
+      let radius = f32::powf(0.001, INV_SAMPLE_RATE / mode_decay_time);
+      let cos_theta = f32::cos(TAU * mode_freq * INV_SAMPLE_RATE);
+
+      // A two pole resonator with zeros at DC and Nyquist. It's normalized so that the energy of
+      // its impulse response doesn't depend on the decay time, which keeps noise and drums fed
+      // through it at about the same loudness regardless of the damping.
+      let normalization = f32::sqrt((1.0 - radius * radius) * 0.5);
+      mode.set_coefficients(
+        [normalization, 0.0, -normalization],
+        [1.0, -2.0 * radius * cos_theta, radius * radius],
+      );
+
+      // Exciting the structure at a node of a mode leaves that mode silent.
+      let position_gain = f32::sin(PI * (index + 1) as f32 * (position * 0.5 + 0.01)).abs();
+      let brightness_gain = f32::powf(ratio, -2.0 * (1.0 - brightness));
+
+      *gain = position_gain * brightness_gain;
+    }
+  }
+}
@@ -0,0 +1,145 @@
+use crate::audio_output::AudioOutput;
+use crate::audio_param::{AudioParam, AudioParamModulationType};
+use crate::{
+  modulate_core::{QUANTUM_SIZE, SAMPLE_RATE},
+  module::Module,
+  rng::Rng,
+};
+
+// Rows of the Voss-McCartney generator. Each row is updated half as often as the previous one, so
+// together they cover the octaves down to ~1 Hz.
+const PINK_ROWS: usize = 15;
+
+// Leak of the integrator turning white noise into brown noise, which keeps it from drifting off.
+const BROWN_LEAK: f32 = 0.998;
+
+// Velvet noise has one impulse of random sign at a random position in every period of this many
+// samples, ~2000 impulses per second.
+const VELVET_PERIOD: usize = SAMPLE_RATE / 2000;
+
+// The gains of the filtered colours are set so that their RMS levels are close to the one of white
+// noise.
+const PINK_GAIN: f32 = 0.25;
+const BROWN_GAIN: f32 = 0.063;
+const BLUE_GAIN: f32 = 2.0;
+
+pub struct Noise {
+  white_output: AudioOutput,
+  pink_output: AudioOutput,
+  brown_output: AudioOutput,
+  blue_output: AudioOutput,
+  velvet_output: AudioOutput,
+
+  level: AudioParam,
+
+  rng: Rng,
+
+  pink_rows: [f32; PINK_ROWS],
+  pink_sum: f32,
+  pink_counter: u32,
+  previous_pink: f32,
+
+  brown: f32,
+
+  velvet_position: usize,
+  velvet_impulse: usize,
+}
+
+impl Module for Noise {
+  fn process(&mut self, _quantum: u64) {
+    for sample in 0..QUANTUM_SIZE {
+      let level = self.level.at(sample);
+
+      let white = self.rng.get_bipolar_f32();
+
+      // The row to update is picked by the number of trailing zeros of the counter, so row 0 is
+      // updated every other sample, row 1 every fourth sample and so on.
+      self.pink_counter = self.pink_counter.wrapping_add(1);
+      let row = self.pink_counter.trailing_zeros() as usize;
+      if row < PINK_ROWS {
+        let value = self.rng.get_bipolar_f32();
+        self.pink_sum += value - self.pink_rows[row];
+        self.pink_rows[row] = value;
+      }
+      let pink = (self.pink_sum + self.rng.get_bipolar_f32()) * PINK_GAIN;
+
+      // Differentiating pink noise tilts its spectrum up by 6 dB per octave, making it blue.
+      let blue = (pink - self.previous_pink) * BLUE_GAIN;
+      self.previous_pink = pink;
+
+      self.brown = self.brown * BROWN_LEAK + self.rng.get_bipolar_f32();
+
+      if self.velvet_position == 0 {
+        self.velvet_impulse = (self.rng.get_f32() * VELVET_PERIOD as f32) as usize;
+      }
+      let velvet = if self.velvet_position == self.velvet_impulse {
+        if self.rng.get_f32() < 0.5 {
+          -1.0
+        } else {
+          1.0
+        }
+      } else {
+        0.0
+      };
+      self.velvet_position = (self.velvet_position + 1) % VELVET_PERIOD;
+
+      self.white_output[sample] = white * level;
+      self.pink_output[sample] = pink * level;
+      self.brown_output[sample] = self.brown * BROWN_GAIN * level;
+      self.blue_output[sample] = blue * level;
+      self.velvet_output[sample] = velvet * level;
+    }
+  }
+
+  fn get_parameters(&mut self) -> Vec<&mut AudioParam> {
+    vec![&mut self.level]
+  }
+
+  fn get_outputs(&mut self) -> Vec<&mut AudioOutput> {
+    vec![
+      &mut self.white_output,
+      &mut self.pink_output,
+      &mut self.brown_output,
+      &mut self.blue_output,
+      &mut self.velvet_output,
+    ]
+  }
+
+  fn reset(&mut self) {
+    self.pink_rows = [0.0; PINK_ROWS];
+    self.pink_sum = 0.0;
+    self.pink_counter = 0;
+    self.previous_pink = 0.0;
+
+    self.brown = 0.0;
+
+    self.velvet_position = 0;
+    self.velvet_impulse = 0;
+  }
+}
+
+impl Noise {
+  pub fn new() -> Box<Noise> {
+    Box::new(Noise {
+      white_output: AudioOutput::default(),
+      pink_output: AudioOutput::default(),
+      brown_output: AudioOutput::default(),
+      blue_output: AudioOutput::default(),
+      velvet_output: AudioOutput::default(),
+
+      level: AudioParam::new(AudioParamModulationType::Additive),
+
+      rng: Rng::unique(),
+
+      pink_rows: [0.0; PINK_ROWS],
+      pink_sum: 0.0,
+      pink_counter: 0,
+      previous_pink: 0.0,
+
+      brown: 0.0,
+
+      velvet_position: 0,
+      velvet_impulse: 0,
+    })
+  }
+}
@@ -1,68 +1,208 @@
+use std::f32::consts::{FRAC_1_SQRT_2, FRAC_PI_4};
+
 use crate::audio_input::AudioInput;
 use crate::audio_output::AudioOutput;
 use crate::audio_param::{AudioParam, AudioParamModulationType};
 use crate::{
   edge_detector::EdgeDetector,
-  modulate_core::{QUANTUM_SIZE, SAMPLE_RATE},
+  modulate_core::{INV_SAMPLE_RATE, QUANTUM_SIZE},
   module::Module,
-  util::exp_curve,
+  rng::Rng,
+  tuning::voltage_to_freq,
+  util::{exp_curve, exp_curve_slope, poly_blamp, poly_blep},
 };
 
 pub struct Oscillator {
   sync_input: AudioInput,
   sync_edge_detector: EdgeDetector,
+  previous_sync: f32,
 
   sin_output: AudioOutput,
   tri_output: AudioOutput,
   saw_output: AudioOutput,
   sqr_output: AudioOutput,
+  left_output: AudioOutput,
+  right_output: AudioOutput,
 
   cv_param: AudioParam,
   fm_param: AudioParam,
   pw_param: AudioParam,
   fine_param: AudioParam,
   level: AudioParam,
+  lin_fm_param: AudioParam,
+  pm_param: AudioParam,
+  sync_mode_param: AudioParam,
+  voices_param: AudioParam,
+  detune_param: AudioParam,
+  spread_param: AudioParam,
+
+  phases: [f32; MAX_VOICES],
+  // Flipped by reverse sync, making the phases run backwards.
+  direction: f32,
+  // Corrections of the hard sync discontinuity for the sample after it, per voice.
+  sync_residuals: [Waveforms; MAX_VOICES],
+  rng: Rng,
+}
 
-  phase: f32,
+// One value for each of the waveforms of a voice.
+#[derive(Clone, Copy, Default)]
+struct Waveforms {
+  sin: f32,
+  tri: f32,
+  saw: f32,
+  sqr: f32,
+}
+
+impl Waveforms {
+  fn zip(self, other: Waveforms, f: impl Fn(f32, f32) -> f32) -> Waveforms {
+    Waveforms {
+      sin: f(self.sin, other.sin),
+      tri: f(self.tri, other.tri),
+      saw: f(self.saw, other.saw),
+      sqr: f(self.sqr, other.sqr),
+    }
+  }
 }
 
-const OSCILLATOR_OVERSAMPLE: usize = 32;
+const MAX_VOICES: usize = 16;
+
+// Values of the sync mode parameter. Any other value disables sync.
+const SYNC_MODE_HARD: i32 = 0;
+const SYNC_MODE_REVERSE: i32 = 1;
+
+// Distance from the phase hard sync resets to at which the waveforms are read on either side of it,
+// which matters when the reset lands on a discontinuity.
+const SYNC_LIMIT_OFFSET: f32 = 1e-5;
+
+// Change of slope at the corners of the `exp_curve` shaped waveforms, per unit of phase. The
+// triangle is built from two curves compressed to half a cycle each, doubling their slopes.
+const SAW_SLOPE_CHANGE: f32 = -4.8;
+const TRI_SLOPE_CHANGE: f32 = 10.4;
 
 impl Module for Oscillator {
   fn process(&mut self, _quantum: u64) {
+    let sin_connected = self.sin_output.is_connected();
+    let tri_connected = self.tri_output.is_connected();
+    let sqr_connected = self.sqr_output.is_connected();
+    let stereo_connected = self.left_output.is_connected() || self.right_output.is_connected();
+    let saw_connected = self.saw_output.is_connected() || stereo_connected;
+
+    // The voice layout is only updated once per quantum, which spares a `powf` per voice and
+    // sample.
+    let voices = (self.voices_param.at(0).round() as usize).clamp(1, MAX_VOICES);
+    let detune = self.detune_param.at(0);
+    let spread = self.spread_param.at(0).clamp(0.0, 1.0);
+
+    let mut ratios = [1.0; MAX_VOICES];
+    let mut left_gains = [FRAC_1_SQRT_2; MAX_VOICES];
+    let mut right_gains = [FRAC_1_SQRT_2; MAX_VOICES];
+    if voices > 1 {
+      for voice in 0..voices {
+        // Voices are spaced evenly from -1 to 1, both in pitch and in the stereo field.
+        let position = voice as f32 / (voices - 1) as f32 * 2.0 - 1.0;
+        ratios[voice] = f32::powf(2.0, position * detune * 0.5 / 12.0);
+
+        let pan = (position * spread + 1.0) * FRAC_PI_4;
+        left_gains[voice] = f32::cos(pan);
+        right_gains[voice] = f32::sin(pan);
+      }
+    }
+
+    // Keep the perceived loudness roughly constant regardless of the voice count.
+    let voice_gain = 1.0 / f32::sqrt(voices as f32);
+
     for sample in 0..QUANTUM_SIZE {
-      let edge = self.sync_edge_detector.step(self.sync_input.at(sample));
+      let sync = self.sync_input.at(sample);
+      let edge = self.sync_edge_detector.step(sync);
 
+      // Hard sync resets the phases a sample late, at the point between this sample and the next
+      // one that matches where the sync input crossed zero. The discontinuity then falls between
+      // two samples which haven't been output yet, so both sides of it can be corrected.
+      let mut sync_offset = None;
       if edge.rose() {
-        self.phase = 0.5;
+        match self.sync_mode_param.at(sample).round() as i32 {
+          SYNC_MODE_HARD => {
+            sync_offset = Some((-self.previous_sync / (sync - self.previous_sync)).clamp(0.0, 1.0))
+          }
+          SYNC_MODE_REVERSE => self.direction = -self.direction,
+          _ => {}
+        }
       }
+      self.previous_sync = sync;
 
       let cv = self.cv_param.at(sample);
       let fm = self.fm_param.at(sample);
-      let pw = self.pw_param.at(sample);
       let fine = self.fine_param.at(sample);
+      let level = self.level.at(sample) * voice_gain;
+      let lin_fm = self.lin_fm_param.at(sample);
+      let pm = self.pm_param.at(sample);
+      let pw = self.pw_param.at(sample).clamp(0.0, 1.0);
+
+      let base_freq = voltage_to_freq(cv + fm + fine / 12.0);
+
+      let mut sin = 0.0;
+      let mut tri = 0.0;
+      let mut saw = 0.0;
+      let mut sqr = 0.0;
+      let mut left = 0.0;
+      let mut right = 0.0;
 
-      let voltage = 5.0 + cv + fm + fine / 12.0;
-      let freq = 13.75 * f32::powf(2.0, voltage);
+      for voice in 0..voices {
+        // Linear FM is added in Hz after the exponential conversion, so it can push the frequency
+        // through zero, at which point the phase runs backwards.
+        let freq = base_freq * ratios[voice] + lin_fm;
 
-      self.sin_output[sample] = 0.;
-      self.tri_output[sample] = 0.;
-      self.saw_output[sample] = 0.;
-      self.sqr_output[sample] = 0.;
+        // The polynomial corrections assume that a discontinuity is passed at most once per
+        // sample. They are symmetric, so only the magnitude of the phase increment matters to them.
+        let phase_increment = (freq * INV_SAMPLE_RATE * self.direction).clamp(-0.5, 0.5);
+        let dt = phase_increment.abs();
 
-      let level_factor = self.level.at(sample) / OSCILLATOR_OVERSAMPLE as f32;
+        // Phase modulation offsets the phase the waveforms are read at, in cycles.
+        let mut phase = self.phases[voice] + pm;
+        phase -= f32::floor(phase);
 
-      for _ in 0..OSCILLATOR_OVERSAMPLE {
-        self.sin_output[sample] += self.sin() * level_factor;
-        self.tri_output[sample] += self.tri() * level_factor;
-        self.saw_output[sample] += self.saw() * level_factor;
-        self.sqr_output[sample] += self.sqr(pw) * level_factor;
+        let mut residual = std::mem::take(&mut self.sync_residuals[voice]);
+        if let Some(offset) = sync_offset {
+          let (before, after) = Oscillator::sync_residuals(phase, phase_increment, pm, pw, offset);
+          residual = residual.zip(before, |a, b| a + b);
+          self.sync_residuals[voice] = after;
+        }
+
+        if sin_connected {
+          sin += Oscillator::sin(phase) + residual.sin;
+        }
+        if tri_connected {
+          tri += Oscillator::tri(phase, dt) + residual.tri;
+        }
+        if saw_connected {
+          let value = Oscillator::saw(phase, dt) + residual.saw;
+          saw += value;
+          left += value * left_gains[voice];
+          right += value * right_gains[voice];
+        }
+        if sqr_connected {
+          sqr += Oscillator::sqr(phase, dt, pw) + residual.sqr;
+        }
 
-        self.phase += freq / SAMPLE_RATE as f32 / OSCILLATOR_OVERSAMPLE as f32;
-        if self.phase > 1.0 {
-          self.phase -= 1.0;
+        let voice_phase = &mut self.phases[voice];
+        *voice_phase = match sync_offset {
+          Some(offset) => 0.5 + phase_increment * (1.0 - offset),
+          None => *voice_phase + phase_increment,
+        };
+        if *voice_phase >= 1.0 {
+          *voice_phase -= 1.0;
+        } else if *voice_phase < 0.0 {
+          *voice_phase += 1.0;
         }
       }
+
+      self.sin_output[sample] = sin * level;
+      self.tri_output[sample] = tri * level;
+      self.saw_output[sample] = saw * level;
+      self.sqr_output[sample] = sqr * level;
+      // The stereo pair carries the saw, spread across the voices.
+      self.left_output[sample] = left * level;
+      self.right_output[sample] = right * level;
     }
   }
 
@@ -77,6 +217,12 @@ impl Module for Oscillator {
       &mut self.pw_param,
       &mut self.fine_param,
       &mut self.level,
+      &mut self.lin_fm_param,
+      &mut self.pm_param,
+      &mut self.sync_mode_param,
+      &mut self.voices_param,
+      &mut self.detune_param,
+      &mut self.spread_param,
     ]
   }
 
@@ -86,57 +232,169 @@ impl Module for Oscillator {
       &mut self.tri_output,
       &mut self.saw_output,
       &mut self.sqr_output,
+      &mut self.left_output,
+      &mut self.right_output,
     ]
   }
+
+  fn reset(&mut self) {
+    self.randomize_phases();
+    self.direction = 1.0;
+    self.sync_residuals = [Waveforms::default(); MAX_VOICES];
+  }
 }
 
 impl Oscillator {
   pub fn new() -> Box<Oscillator> {
-    Box::new(Oscillator {
+    let mut osc = Box::new(Oscillator {
       sync_input: AudioInput::default(),
       sync_edge_detector: EdgeDetector::new(0.0),
+      previous_sync: 0.0,
 
       sin_output: AudioOutput::default(),
       tri_output: AudioOutput::default(),
       saw_output: AudioOutput::default(),
       sqr_output: AudioOutput::default(),
+      left_output: AudioOutput::default(),
+      right_output: AudioOutput::default(),
 
       cv_param: AudioParam::new(AudioParamModulationType::Additive),
       fm_param: AudioParam::new(AudioParamModulationType::Multiplicative),
       pw_param: AudioParam::new(AudioParamModulationType::Additive),
       fine_param: AudioParam::new(AudioParamModulationType::Additive),
       level: AudioParam::new(AudioParamModulationType::Additive),
+      lin_fm_param: AudioParam::new(AudioParamModulationType::Multiplicative),
+      pm_param: AudioParam::new(AudioParamModulationType::Multiplicative),
+      sync_mode_param: AudioParam::new(AudioParamModulationType::Additive),
+      voices_param: AudioParam::new(AudioParamModulationType::Additive),
+      detune_param: AudioParam::new(AudioParamModulationType::Additive),
+      spread_param: AudioParam::new(AudioParamModulationType::Additive),
+
+      phases: [0.0; MAX_VOICES],
+      direction: 1.0,
+      sync_residuals: [Waveforms::default(); MAX_VOICES],
+      rng: Rng::unique(),
+    });
+    osc.randomize_phases();
 
-      phase: 0.0,
-    })
+    osc
+  }
+
+  // The first voice always starts from zero, so a single voice oscillator behaves predictably. The
+  // rest start at random phases to avoid the unison voices summing up to a loud transient.
+  fn randomize_phases(&mut self) {
+    self.phases[0] = 0.0;
+    for phase in self.phases.iter_mut().skip(1) {
+      *phase = self.rng.get_f32();
+    }
   }
 
-  fn sin(&self) -> f32 {
-    let half_phase = self.phase < 0.5;
-    let x = self.phase - if half_phase { 0.25 } else { 0.75 };
+  fn sin(phase: f32) -> f32 {
+    let half_phase = phase < 0.5;
+    let x = phase - if half_phase { 0.25 } else { 0.75 };
     let v = 1.0 - 16.0 * f32::powf(x, 2.);
     v * if half_phase { 1. } else { -1. }
   }
 
-  fn tri(&self) -> f32 {
-    let mut x = self.phase + 0.25;
+  fn tri(phase: f32, dt: f32) -> f32 {
+    let mut x = phase + 0.25;
     x -= f32::trunc(x);
     let half_x = x >= 0.5;
+    let corner = x;
     x *= 2.0;
     x -= f32::trunc(x);
-    exp_curve(x) * if half_x { 1. } else { -1. }
+    let naive = exp_curve(x) * if half_x { 1. } else { -1. };
+
+    let mut opposite_corner = corner + 0.5;
+    opposite_corner -= f32::trunc(opposite_corner);
+
+    naive + TRI_SLOPE_CHANGE * dt * (poly_blamp(corner, dt) - poly_blamp(opposite_corner, dt))
   }
 
-  fn saw(&self) -> f32 {
-    let x = self.phase + 0.5;
-    exp_curve(x - f32::trunc(x))
+  fn saw(phase: f32, dt: f32) -> f32 {
+    let mut x = phase + 0.5;
+    x -= f32::trunc(x);
+    exp_curve(x) + 2.0 * poly_blep(x, dt) + SAW_SLOPE_CHANGE * dt * poly_blamp(x, dt)
   }
 
-  fn sqr(&self, pw: f32) -> f32 {
-    if self.phase > pw {
-      -1.0
-    } else {
-      1.0
-    }
+  fn sqr(phase: f32, dt: f32, pw: f32) -> f32 {
+    let naive = if phase > pw { -1.0 } else { 1.0 };
+
+    let mut falling_edge = phase - pw + 1.0;
+    falling_edge -= f32::trunc(falling_edge);
+
+    naive + 2.0 * (poly_blep(phase, dt) - poly_blep(falling_edge, dt))
+  }
+
+  // Values and slopes per unit of phase of the waveforms, without any corrections.
+  fn naive(phase: f32, pw: f32) -> (Waveforms, Waveforms) {
+    let phase = phase - f32::floor(phase);
+
+    let half_phase = phase < 0.5;
+    let sin_x = phase - if half_phase { 0.25 } else { 0.75 };
+    let sin_sign = if half_phase { 1. } else { -1. };
+
+    let mut tri_x = phase + 0.25;
+    tri_x -= f32::trunc(tri_x);
+    let tri_sign = if tri_x >= 0.5 { 1. } else { -1. };
+    tri_x *= 2.0;
+    tri_x -= f32::trunc(tri_x);
+
+    let mut saw_x = phase + 0.5;
+    saw_x -= f32::trunc(saw_x);
+
+    let values = Waveforms {
+      sin: Oscillator::sin(phase),
+      tri: exp_curve(tri_x) * tri_sign,
+      saw: exp_curve(saw_x),
+      sqr: if phase > pw { -1.0 } else { 1.0 },
+    };
+    let slopes = Waveforms {
+      sin: -32.0 * sin_x * sin_sign,
+      tri: 2.0 * exp_curve_slope(tri_x) * tri_sign,
+      saw: exp_curve_slope(saw_x),
+      sqr: 0.0,
+    };
+
+    (values, slopes)
+  }
+
+  // Corrections of a hard sync reset `offset` samples after the current sample, for the current
+  // sample and the next one. The jumps in value and slope are taken from the waveforms right after
+  // the reset for the current sample, and from right before the phase the voice resets to for the
+  // next one, as the corrections of the waveforms' own discontinuities take care of the rest once
+  // the voice runs from there.
+  fn sync_residuals(
+    phase: f32,
+    phase_increment: f32,
+    pm: f32,
+    pw: f32,
+    offset: f32,
+  ) -> (Waveforms, Waveforms) {
+    let direction = if phase_increment < 0.0 { -1.0 } else { 1.0 };
+    let reset_phase = 0.5 + pm;
+
+    let (old_values, old_slopes) = Oscillator::naive(phase + phase_increment * offset, pw);
+    let (new_values, new_slopes) =
+      Oscillator::naive(reset_phase + direction * SYNC_LIMIT_OFFSET, pw);
+    let (entry_values, entry_slopes) =
+      Oscillator::naive(reset_phase - direction * SYNC_LIMIT_OFFSET, pw);
+
+    // The same residuals as `poly_blep` and `poly_blamp` would give, in samples.
+    let blep_before = 0.5 * (1.0 - offset) * (1.0 - offset);
+    let blep_after = -0.5 * offset * offset;
+    let blamp_before = (1.0 - offset) * (1.0 - offset) * (1.0 - offset) / 6.0;
+    let blamp_after = offset * offset * offset / 6.0;
+
+    let residual = |values: Waveforms, slopes: Waveforms, blep: f32, blamp: f32| {
+      let jumps = values.zip(old_values, |new, old| (new - old) * blep);
+      let slope_changes = slopes.zip(old_slopes, |new, old| (new - old) * phase_increment * blamp);
+      jumps.zip(slope_changes, |jump, slope_change| jump + slope_change)
+    };
+
+    (
+      residual(new_values, new_slopes, blep_before, blamp_before),
+      residual(entry_values, entry_slopes, blep_after, blamp_after),
+    )
   }
 }
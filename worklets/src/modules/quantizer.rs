@@ -0,0 +1,138 @@
+use crate::audio_input::AudioInput;
+use crate::audio_output::AudioOutput;
+use crate::audio_param::{AudioParam, AudioParamModulationType};
+use crate::{
+  modulate_core::{QUANTUM_SIZE, SAMPLE_RATE},
+  module::{Module, ModuleMessage},
+  tuning::{Tuning, REFERENCE_NOTE},
+};
+
+// The reference note is an A, so C is 9 notes below it.
+const C_OFFSET: i32 = 9;
+
+// How much closer in volts another note has to be before the output leaves the current note,
+// which keeps slowly moving or noisy inputs from flickering between two notes.
+const HYSTERESIS: f32 = 0.05 / 12.0;
+
+const TRIGGER_LENGTH: usize = SAMPLE_RATE / 1000;
+
+// Snaps the input to the closest enabled note of the tuning. The notes are enabled by their key on
+// the keyboard, so with a tuning other than 12 tone equal temperament the same keys are used, but
+// with the voltages of the tuning.
+pub struct Quantizer {
+  input: AudioInput,
+  cv_output: AudioOutput,
+  trigger_output: AudioOutput,
+
+  root_param: AudioParam,
+  transpose_param: AudioParam,
+
+  // Enabled notes of the scale from C to B, before being moved to the root.
+  notes: [bool; 12],
+  // The current note and its voltage, if there's one.
+  current_note: Option<(i32, f32)>,
+  trigger_timer: usize,
+
+  tuning: *const Tuning,
+}
+
+impl Module for Quantizer {
+  fn process(&mut self, _quantum: u64) {
+    let tuning = unsafe { &*self.tuning };
+    let root = self.root_param.at(0).round() as i32;
+    let notes = self.notes;
+    let is_enabled =
+      |note: i32| notes[(note - REFERENCE_NOTE + C_OFFSET - root).rem_euclid(12) as usize];
+
+    // Finding the nearest note goes through the whole tuning, so it's only done when the input
+    // changes.
+    let mut previous_input = None;
+    let mut nearest = None;
+
+    for sample in 0..QUANTUM_SIZE {
+      let input = self.input.at(sample);
+      let transpose = self.transpose_param.at(sample).round() as i32;
+
+      if previous_input != Some(input) {
+        previous_input = Some(input);
+        nearest = tuning.nearest_note(input, is_enabled);
+      }
+
+      let Some((nearest_note, nearest_voltage)) = nearest else {
+        // With no notes enabled there's nothing to snap to, so the input passes through.
+        self.current_note = None;
+        self.cv_output[sample] = input + transpose as f32 / 12.0;
+        self.trigger_output[sample] = 0.0;
+        continue;
+      };
+
+      let (note, voltage) = match self.current_note {
+        Some((current_note, current_voltage))
+          if is_enabled(current_note)
+            && tuning.note_to_voltage(current_note) == Some(current_voltage)
+            && (input - current_voltage).abs() <= (input - nearest_voltage).abs() + HYSTERESIS =>
+        {
+          (current_note, current_voltage)
+        }
+        _ => (nearest_note, nearest_voltage),
+      };
+
+      if self.current_note.map(|(current_note, _)| current_note) != Some(note) {
+        self.trigger_timer = TRIGGER_LENGTH;
+      }
+      self.current_note = Some((note, voltage));
+
+      // Transposing moves by notes of the tuning, staying on the note when there's no such note.
+      self.cv_output[sample] = tuning.note_to_voltage(note + transpose).unwrap_or(voltage);
+
+      if self.trigger_timer != 0 {
+        self.trigger_output[sample] = 1.0;
+        self.trigger_timer -= 1;
+      } else {
+        self.trigger_output[sample] = 0.0;
+      }
+    }
+  }
+
+  fn on_message(&mut self, message: ModuleMessage) {
+    if let ModuleMessage::QuantizerSetNotes { notes } = message {
+      self.notes = notes;
+    }
+  }
+
+  fn get_inputs(&mut self) -> Vec<&mut AudioInput> {
+    vec![&mut self.input]
+  }
+
+  fn get_parameters(&mut self) -> Vec<&mut AudioParam> {
+    vec![&mut self.root_param, &mut self.transpose_param]
+  }
+
+  fn get_outputs(&mut self) -> Vec<&mut AudioOutput> {
+    vec![&mut self.cv_output, &mut self.trigger_output]
+  }
+
+  fn reset(&mut self) {
+    self.current_note = None;
+    self.trigger_timer = 0;
+  }
+}
+
+impl Quantizer {
+  pub fn new(tuning: &Tuning) -> Box<Quantizer> {
+    Box::new(Quantizer {
+      input: AudioInput::default(),
+      cv_output: AudioOutput::default(),
+      trigger_output: AudioOutput::default(),
+
+      root_param: AudioParam::new(AudioParamModulationType::Additive),
+      transpose_param: AudioParam::new(AudioParamModulationType::Additive),
+
+      notes: [true; 12],
+      current_note: None,
+      trigger_timer: 0,
+
+      tuning,
+    })
+  }
+}
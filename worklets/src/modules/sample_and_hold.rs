@@ -0,0 +1,124 @@
+use crate::audio_input::AudioInput;
+use crate::audio_output::AudioOutput;
+use crate::audio_param::{AudioParam, AudioParamModulationType};
+use crate::{
+  edge_detector::EdgeDetector,
+  modulate_core::{INV_SAMPLE_RATE, QUANTUM_SIZE},
+  module::Module,
+  rng::Rng,
+};
+
+// Holds a new value on every rising edge of the trigger.
+const MODE_SAMPLE_AND_HOLD: usize = 0;
+// Follows the input while the trigger is high, and holds the last value while it's low.
+const MODE_TRACK_AND_HOLD: usize = 1;
+
+pub struct SampleAndHold {
+  input: AudioInput,
+  trigger_input: AudioInput,
+  output: AudioOutput,
+
+  mode_param: AudioParam,
+  rate_param: AudioParam,
+  slew_param: AudioParam,
+
+  trigger_edge_detector: EdgeDetector,
+  // Phase of the internal clock, used when there's nothing patched to the trigger input.
+  clock_phase: f32,
+  // Source of the values when there's nothing patched to the input.
+  rng: Rng,
+
+  held: f32,
+  smoothed: f32,
+}
+
+impl Module for SampleAndHold {
+  fn process(&mut self, _quantum: u64) {
+    let mode = self.mode_param.at(0).round() as usize;
+    let uses_internal_clock = !self.trigger_input.is_connected();
+    let uses_internal_noise = !self.input.is_connected();
+
+    for sample in 0..QUANTUM_SIZE {
+      let trigger = if uses_internal_clock {
+        self.clock_phase += self.rate_param.at(sample) * INV_SAMPLE_RATE;
+        self.clock_phase -= f32::floor(self.clock_phase);
+        if self.clock_phase < 0.5 {
+          1.0
+        } else {
+          0.0
+        }
+      } else {
+        self.trigger_input.at(sample)
+      };
+      let edge = self.trigger_edge_detector.step(trigger);
+
+      let should_sample = match mode {
+        MODE_SAMPLE_AND_HOLD => edge.rose(),
+        MODE_TRACK_AND_HOLD => edge.is_high(),
+        _ => false,
+      };
+
+      if should_sample {
+        self.held = if uses_internal_noise {
+          self.rng.get_bipolar_f32()
+        } else {
+          self.input.at(sample)
+        };
+      }
+
+      // The slew is the time constant of a one pole lowpass filter smoothing the held value.
+      let slew = self.slew_param.at(sample);
+      if slew > 0.0 {
+        let coefficient = 1.0 - f32::exp(-INV_SAMPLE_RATE / slew);
+        self.smoothed += (self.held - self.smoothed) * coefficient;
+      } else {
+        self.smoothed = self.held;
+      }
+
+      self.output[sample] = self.smoothed;
+    }
+  }
+
+  fn get_inputs(&mut self) -> Vec<&mut AudioInput> {
+    vec![&mut self.input, &mut self.trigger_input]
+  }
+
+  fn get_parameters(&mut self) -> Vec<&mut AudioParam> {
+    vec![
+      &mut self.mode_param,
+      &mut self.rate_param,
+      &mut self.slew_param,
+    ]
+  }
+
+  fn get_outputs(&mut self) -> Vec<&mut AudioOutput> {
+    vec![&mut self.output]
+  }
+
+  fn reset(&mut self) {
+    self.clock_phase = 0.0;
+    self.held = 0.0;
+    self.smoothed = 0.0;
+  }
+}
+
+impl SampleAndHold {
+  pub fn new() -> Box<SampleAndHold> {
+    Box::new(SampleAndHold {
+      input: AudioInput::default(),
+      trigger_input: AudioInput::default(),
+      output: AudioOutput::default(),
+
+      mode_param: AudioParam::new(AudioParamModulationType::Additive),
+      rate_param: AudioParam::new(AudioParamModulationType::Additive),
+      slew_param: AudioParam::new(AudioParamModulationType::Additive),
+
+      trigger_edge_detector: EdgeDetector::default(),
+      clock_phase: 0.0,
+      rng: Rng::unique(),
+
+      held: 0.0,
+      smoothed: 0.0,
+    })
+  }
+}
@@ -17,7 +17,7 @@ pub struct Sampler {
   output: AudioOutput,
   pos: f64,
   sample: Option<Box<[f32]>>,
-  events: Vec<ModuleEvent>,
+  pending_event: Option<ModuleEvent>,
 }
 
 impl Module for Sampler {
@@ -74,8 +74,8 @@ impl Module for Sampler {
     vec![&mut self.output]
   }
 
-  fn pop_event(&mut self) -> Option<ModuleEvent> {
-    self.events.pop()
+  fn pop_event(&mut self) -> Option<(usize, ModuleEvent)> {
+    self.pending_event.take().map(|event| (0, event))
   }
 
   fn on_message(&mut self, message: ModuleMessage) {
@@ -84,9 +84,7 @@ impl Module for Sampler {
         let zeros = vec![0.0; size];
         self.sample = Some(zeros.into_boxed_slice());
         let ptr = self.sample.as_ref().unwrap().as_ptr() as usize;
-        self
-          .events
-          .push(ModuleEvent::SamplerAllocateSuccess { ptr })
+        self.pending_event = Some(ModuleEvent::SamplerAllocateSuccess { ptr });
       }
       _ => panic!("sampler: received unhandled message"),
     }
@@ -109,7 +107,7 @@ impl Sampler {
       output: AudioOutput::default(),
       pos: 0.0,
       sample: None,
-      events: vec![],
+      pending_event: None,
     })
   }
 }
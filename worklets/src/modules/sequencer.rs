@@ -4,141 +4,421 @@ use crate::{
   audio_param::AudioParam,
   edge_detector::EdgeDetector,
   modulate_core::{QUANTUM_SIZE, SAMPLE_RATE},
-  module::{Module, ModuleEvent, ModuleMessage},
+  module::{Module, ModuleMessage, SequencerChainEntry},
+  rng::Rng,
+  tuning::{Tuning, REFERENCE_NOTE},
+  ui_state::UiState,
   util::lerp,
 };
 
+const NUM_STEPS: usize = 32;
+const NUM_PATTERNS: usize = 16;
+const MAX_CHAIN_LENGTH: usize = 64;
+const MAX_RATCHETS: u32 = 8;
+
+// Clock period assumed until two clock edges have been seen, 16th notes at 120 BPM.
+const DEFAULT_CLOCK_PERIOD: usize = SAMPLE_RATE / 8;
+
+const DIRECTION_REVERSE: usize = 1;
+const DIRECTION_PING_PONG: usize = 2;
+const DIRECTION_RANDOM: usize = 3;
+// Moves a step forward, a step back or stays, forward being the most likely.
+const DIRECTION_BROWNIAN: usize = 4;
+
 #[derive(Clone, Copy)]
 struct Note {
-  voltage: f32,
+  note: i32,
   glide: bool,
   gate: bool,
+  probability: f32,
+  ratchets: u32,
+  slide: f32,
+  skip: bool,
+  accent: bool,
 }
 
 impl Default for Note {
   fn default() -> Note {
     Note {
-      voltage: 0.0,
+      note: REFERENCE_NOTE,
       gate: true,
       glide: false,
+      probability: 1.0,
+      ratchets: 1,
+      slide: 0.0,
+      skip: false,
+      accent: false,
     }
   }
 }
 
-fn note_to_voltage(note: &String) -> f32 {
+// Semitones from A, which is the reference note in octave 4.
+fn note_to_semitones(note: &String) -> i32 {
   match note.as_str() {
-    "C" => -9.0 / 12.0,
-    "C#" => -8.0 / 12.0,
-    "D" => -7.0 / 12.0,
-    "D#" => -6.0 / 12.0,
-    "E" => -5.0 / 12.0,
-    "F" => -4.0 / 12.0,
-    "F#" => -3.0 / 12.0,
-    "G" => -2.0 / 12.0,
-    "G#" => -1.0 / 12.0,
-    "A" => 0.0 / 12.0,
-    "A#" => 1.0 / 12.0,
-    "B" => 2.0 / 12.0,
+    "C" => -9,
+    "C#" => -8,
+    "D" => -7,
+    "D#" => -6,
+    "E" => -5,
+    "F" => -4,
+    "F#" => -3,
+    "G" => -2,
+    "G#" => -1,
+    "A" => 0,
+    "A#" => 1,
+    "B" => 2,
     _ => panic!("unknown note {}", note),
   }
 }
 
-#[derive(Default)]
 pub struct Sequencer {
   gate_input: AudioInput,
+  reset_input: AudioInput,
   sequence_length: AudioParam,
   glide: AudioParam,
+  direction: AudioParam,
+  pattern_param: AudioParam,
   cv_output: AudioOutput,
   gate_output: AudioOutput,
+  accent_output: AudioOutput,
 
-  notes: [Note; 32],
+  patterns: [[Note; NUM_STEPS]; NUM_PATTERNS],
+  pattern: usize,
   current_step: usize,
+  // Clock edges since the pattern started over. Patterns only change once a whole pattern has
+  // been played, whatever the direction.
+  steps_played: usize,
+
+  // Patterns played in order, each repeated the amount of times of its entry.
+  chain: Vec<SequencerChainEntry>,
+  chain_position: usize,
+  chain_repeat: u32,
+
+  // The pattern, the step and the chain position, read by the UI.
+  playhead: UiState<[f32; 3]>,
   edge_detector: EdgeDetector,
+  reset_edge_detector: EdgeDetector,
+  // After a reset the next clock edge plays the first step instead of advancing.
+  is_reset: bool,
+  is_moving_forward: bool,
+  // Whether the current step won its probability roll.
+  is_step_playing: bool,
+  rng: Rng,
+  // Samples since the last clock edge, and between the last two, which the ratchets are
+  // relative to.
   time: usize,
+  clock_period: usize,
   previous_voltage: f32,
+  voltage: f32,
 
-  events: Vec<ModuleEvent>,
+  tuning: *const Tuning,
 }
 
 impl Module for Sequencer {
   fn process(&mut self, _quantum: u64) {
+    let tuning = unsafe { &*self.tuning };
+
     for sample in 0..QUANTUM_SIZE {
+      if self
+        .reset_edge_detector
+        .step(self.reset_input.at(sample))
+        .rose()
+      {
+        self.is_reset = true;
+      }
+
       let edge = self.edge_detector.step(self.gate_input.at(sample));
 
-      let note = &self.notes[self.current_step];
+      if edge.rose() {
+        let length = (self.sequence_length.at(sample) as usize).clamp(1, NUM_STEPS);
+        let direction = self.direction.at(sample).round() as usize;
+
+        self.previous_voltage = self.voltage;
+        if self.time > 0 {
+          self.clock_period = self.time;
+        }
+        self.time = 0;
+
+        if self.is_reset {
+          self.chain_position = 0;
+          self.chain_repeat = 0;
+          self.steps_played = 0;
+          self.pattern = self.first_pattern(sample);
+        } else {
+          self.steps_played += 1;
+
+          if self.steps_played >= length {
+            self.steps_played = 0;
+
+            let pattern = self.next_pattern(sample);
+            if pattern != self.pattern {
+              self.pattern = pattern;
+              // Play the new pattern from its first step.
+              self.is_reset = true;
+            }
+          }
+        }
+
+        self.advance(length, direction);
+
+        let note = &self.patterns[self.pattern][self.current_step];
+        self.is_step_playing = note.gate && self.rng.get_f32() < note.probability;
+      }
+
+      let note = &self.patterns[self.pattern][self.current_step];
+      // Notes left unmapped by the tuning hold the previous voltage and don't open the gate.
+      let note_voltage = tuning.note_to_voltage(note.note);
+      let is_playing = self.is_step_playing && note_voltage.is_some();
+      let note_voltage = note_voltage.unwrap_or(self.previous_voltage);
 
       let voltage = if note.glide {
+        let glide_time = if note.slide > 0.0 {
+          note.slide
+        } else {
+          self.glide.at(sample)
+        };
         let t = f32::clamp(
-          self.time as f32 / f32::max(1.0, SAMPLE_RATE as f32 * self.glide.at(sample)),
+          self.time as f32 / f32::max(1.0, SAMPLE_RATE as f32 * glide_time),
           0.0,
           1.0,
         );
 
-        lerp(self.previous_voltage, note.voltage, t)
+        lerp(self.previous_voltage, note_voltage, t)
       } else {
-        note.voltage
+        note_voltage
       };
 
-      if edge.rose() {
-        self.current_step += 1;
-        self.time = 0;
-        self.previous_voltage = voltage;
+      self.voltage = voltage;
+      self.cv_output[sample] = voltage;
 
-        if self.current_step >= self.sequence_length.at(sample) as usize {
-          self.current_step = 0;
+      // Ratchets repeat the step within the clock period, at half of each repeat.
+      let ratchets = note.ratchets.clamp(1, MAX_RATCHETS) as usize;
+      let gate = if !is_playing {
+        0.0
+      } else if ratchets == 1 {
+        self.gate_input.at(sample)
+      } else {
+        let ratchet_period = (self.clock_period / ratchets).max(1);
+        if self.time / ratchet_period < ratchets && self.time % ratchet_period < ratchet_period / 2
+        {
+          1.0
+        } else {
+          0.0
         }
+      };
 
-        self.events.push(ModuleEvent::SequencerAdvance {
-          position: self.current_step,
-        });
-      }
-
-      self.cv_output[sample] = voltage;
+      self.gate_output[sample] = gate;
+      self.accent_output[sample] = if note.accent { gate } else { 0.0 };
 
       self.time += 1;
-
-      if note.gate {
-        self.gate_output[sample] = self.gate_input.at(sample);
-      } else {
-        self.gate_output[sample] = 0.0;
-      }
     }
-  }
 
-  fn pop_event(&mut self) -> Option<ModuleEvent> {
-    self.events.pop()
+    self.playhead.write([
+      self.pattern as f32,
+      self.current_step as f32,
+      self.chain_position as f32,
+    ]);
   }
 
   fn on_message(&mut self, message: ModuleMessage) {
     match message {
-      ModuleMessage::SequencerSetNotes { notes } => {
-        for (i, note) in notes.iter().enumerate() {
-          let voltage = note_to_voltage(&note.name) - 4.0 + note.octave;
+      ModuleMessage::SequencerSetNotes { pattern, notes } => {
+        let Some(pattern) = self.patterns.get_mut(pattern) else {
+          return;
+        };
 
-          self.notes[i].voltage = voltage;
-          self.notes[i].gate = note.gate;
-          self.notes[i].glide = note.glide;
+        for (i, note) in notes.iter().enumerate().take(NUM_STEPS) {
+          let octave = note.octave as i32 - 4;
+
+          pattern[i] = Note {
+            note: REFERENCE_NOTE + note_to_semitones(&note.name) + octave * 12,
+            gate: note.gate,
+            glide: note.glide,
+            probability: note.probability,
+            ratchets: note.ratchets,
+            slide: note.slide,
+            skip: note.skip,
+            accent: note.accent,
+          };
         }
       }
+      ModuleMessage::SequencerReset => self.is_reset = true,
+      ModuleMessage::SequencerSetChain { chain } => {
+        self.chain.clear();
+        self.chain.extend(
+          chain
+            .iter()
+            .filter(|entry| entry.pattern < NUM_PATTERNS)
+            .take(MAX_CHAIN_LENGTH),
+        );
+        self.chain_position = 0;
+        self.chain_repeat = 0;
+      }
       _ => panic!("sequencer: received unhandled message"),
     };
   }
 
   fn get_inputs(&mut self) -> Vec<&mut AudioInput> {
-    vec![&mut self.gate_input]
+    vec![&mut self.gate_input, &mut self.reset_input]
   }
 
   fn get_parameters(&mut self) -> Vec<&mut AudioParam> {
-    vec![&mut self.sequence_length, &mut self.glide]
+    vec![
+      &mut self.sequence_length,
+      &mut self.glide,
+      &mut self.direction,
+      &mut self.pattern_param,
+    ]
   }
 
   fn get_outputs(&mut self) -> Vec<&mut AudioOutput> {
-    vec![&mut self.cv_output, &mut self.gate_output]
+    vec![
+      &mut self.cv_output,
+      &mut self.gate_output,
+      &mut self.accent_output,
+    ]
+  }
+
+  fn get_pointers(&mut self) -> Vec<usize> {
+    vec![self.playhead.as_ptr()]
   }
 }
 
 impl Sequencer {
-  pub fn new() -> Box<Sequencer> {
-    Box::new(Sequencer::default())
+  pub fn new(tuning: &Tuning) -> Box<Sequencer> {
+    Box::new(Sequencer {
+      gate_input: AudioInput::default(),
+      reset_input: AudioInput::default(),
+      sequence_length: AudioParam::default(),
+      glide: AudioParam::default(),
+      direction: AudioParam::default(),
+      pattern_param: AudioParam::default(),
+      cv_output: AudioOutput::default(),
+      gate_output: AudioOutput::default(),
+      accent_output: AudioOutput::default(),
+
+      patterns: [[Note::default(); NUM_STEPS]; NUM_PATTERNS],
+      pattern: 0,
+      current_step: 0,
+      steps_played: 0,
+
+      chain: Vec::with_capacity(MAX_CHAIN_LENGTH),
+      chain_position: 0,
+      chain_repeat: 0,
+
+      playhead: UiState::new([0.0; 3]),
+      edge_detector: EdgeDetector::default(),
+      reset_edge_detector: EdgeDetector::default(),
+      is_reset: true,
+      is_moving_forward: true,
+      is_step_playing: false,
+      rng: Rng::unique(),
+      time: 0,
+      clock_period: DEFAULT_CLOCK_PERIOD,
+      previous_voltage: 0.0,
+      voltage: 0.0,
+
+      tuning,
+    })
+  }
+
+  fn selected_pattern(&mut self, sample: usize) -> usize {
+    (self.pattern_param.at(sample).round().max(0.0) as usize).min(NUM_PATTERNS - 1)
+  }
+
+  fn first_pattern(&mut self, sample: usize) -> usize {
+    match self.chain.first() {
+      Some(entry) => entry.pattern,
+      None => self.selected_pattern(sample),
+    }
+  }
+
+  // The pattern to play after the current one has ended, moving along the chain once the current
+  // entry has been repeated enough.
+  fn next_pattern(&mut self, sample: usize) -> usize {
+    if self.chain.is_empty() {
+      return self.selected_pattern(sample);
+    }
+
+    self.chain_repeat += 1;
+    if self.chain_repeat >= self.chain[self.chain_position].repeats.max(1) {
+      self.chain_repeat = 0;
+      self.chain_position = (self.chain_position + 1) % self.chain.len();
+    }
+
+    self.chain[self.chain_position].pattern
+  }
+
+  fn advance(&mut self, length: usize, direction: usize) {
+    let current_step = self.current_step.min(length - 1);
+
+    if self.is_reset {
+      self.is_reset = false;
+      self.is_moving_forward = true;
+
+      // Start from the last step when going backwards, and from the first step otherwise.
+      let first_step = if direction == DIRECTION_REVERSE {
+        self.find_step(0, false, true, length)
+      } else {
+        self.find_step(length - 1, true, true, length)
+      };
+      self.current_step = first_step.unwrap_or(current_step);
+      return;
+    }
+
+    let next_step = match direction {
+      DIRECTION_REVERSE => self.find_step(current_step, false, true, length),
+      DIRECTION_PING_PONG => self
+        .find_step(current_step, self.is_moving_forward, false, length)
+        .or_else(|| {
+          self.is_moving_forward = !self.is_moving_forward;
+          self.find_step(current_step, self.is_moving_forward, false, length)
+        }),
+      DIRECTION_RANDOM => {
+        let num_steps = (0..length)
+          .filter(|step| !self.patterns[self.pattern][*step].skip)
+          .count();
+        let nth = self.rng.get_u32() as usize % num_steps.max(1);
+        (0..length)
+          .filter(|step| !self.patterns[self.pattern][*step].skip)
+          .nth(nth)
+      }
+      DIRECTION_BROWNIAN => {
+        let roll = self.rng.get_f32();
+        if roll < 0.5 {
+          self.find_step(current_step, true, true, length)
+        } else if roll < 0.75 {
+          self.find_step(current_step, false, true, length)
+        } else {
+          Some(current_step)
+        }
+      }
+      _ => self.find_step(current_step, true, true, length),
+    };
+
+    // When every step is skipped the sequencer stays where it is.
+    self.current_step = next_step.unwrap_or(current_step);
+  }
+
+  // The next step in the direction that isn't skipped, wrapping around the ends or stopping at
+  // them.
+  fn find_step(&self, from: usize, forward: bool, wrap: bool, length: usize) -> Option<usize> {
+    let mut step = from as isize;
+
+    for _ in 0..length {
+      step += if forward { 1 } else { -1 };
+
+      if wrap {
+        step = step.rem_euclid(length as isize);
+      } else if step < 0 || step >= length as isize {
+        return None;
+      }
+
+      if !self.patterns[self.pattern][step as usize].skip {
+        return Some(step as usize);
+      }
+    }
+
+    None
   }
 }
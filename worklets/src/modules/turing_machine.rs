@@ -0,0 +1,137 @@
+use crate::audio_input::AudioInput;
+use crate::audio_output::AudioOutput;
+use crate::audio_param::{AudioParam, AudioParamModulationType};
+use crate::{edge_detector::EdgeDetector, modulate_core::QUANTUM_SIZE, module::Module, rng::Rng};
+
+const MIN_LENGTH: u32 = 2;
+const MAX_LENGTH: u32 = 16;
+const NUM_GATES: usize = 4;
+
+// The CV is made from the lowest bits of the register.
+const CV_BITS: u32 = 8;
+const CV_MAX: f32 = ((1 << CV_BITS) - 1) as f32;
+
+// A looping shift register. On every clock the bit leaving the loop is fed back into it, flipped
+// at the change probability, so the loop is locked at no change and fully random at half change.
+// Full change flips every bit, which plays the loop and then its inverse.
+pub struct TuringMachine {
+  clock_input: AudioInput,
+  reset_input: AudioInput,
+  cv_output: AudioOutput,
+  gate_outputs: [AudioOutput; NUM_GATES],
+
+  length_param: AudioParam,
+  change_param: AudioParam,
+  scale_param: AudioParam,
+  seed_param: AudioParam,
+
+  clock_edge_detector: EdgeDetector,
+  reset_edge_detector: EdgeDetector,
+
+  register: u16,
+  rng: Rng,
+  // The register and the random numbers start over from the seed on reset. A seed of 0 picks a
+  // different one every time.
+  seed: u64,
+}
+
+impl Module for TuringMachine {
+  fn process(&mut self, _quantum: u64) {
+    let seed = self.seed_param.at(0).round().max(0.0) as u64;
+    if seed != self.seed {
+      self.seed = seed;
+      self.restart();
+    }
+
+    for sample in 0..QUANTUM_SIZE {
+      if self
+        .reset_edge_detector
+        .step(self.reset_input.at(sample))
+        .rose()
+      {
+        self.restart();
+      }
+
+      let clock = self.clock_edge_detector.step(self.clock_input.at(sample));
+
+      if clock.rose() {
+        let length = (self.length_param.at(sample).round() as u32).clamp(MIN_LENGTH, MAX_LENGTH);
+        let change = self.change_param.at(sample).clamp(0.0, 1.0);
+
+        let mut bit = (self.register >> (length - 1)) & 1;
+        if self.rng.get_f32() < change {
+          bit ^= 1;
+        }
+
+        self.register = (self.register << 1) | bit;
+      }
+
+      let value = (self.register as u32 & ((1 << CV_BITS) - 1)) as f32 / CV_MAX;
+      self.cv_output[sample] = value * self.scale_param.at(sample);
+
+      for (gate, output) in self.gate_outputs.iter_mut().enumerate() {
+        output[sample] = if clock.is_high() && (self.register >> gate) & 1 == 1 {
+          1.0
+        } else {
+          0.0
+        };
+      }
+    }
+  }
+
+  fn get_inputs(&mut self) -> Vec<&mut AudioInput> {
+    vec![&mut self.clock_input, &mut self.reset_input]
+  }
+
+  fn get_parameters(&mut self) -> Vec<&mut AudioParam> {
+    vec![
+      &mut self.length_param,
+      &mut self.change_param,
+      &mut self.scale_param,
+      &mut self.seed_param,
+    ]
+  }
+
+  fn get_outputs(&mut self) -> Vec<&mut AudioOutput> {
+    let [gate0, gate1, gate2, gate3] = &mut self.gate_outputs;
+    vec![&mut self.cv_output, gate0, gate1, gate2, gate3]
+  }
+
+  fn reset(&mut self) {
+    self.restart();
+  }
+}
+
+impl TuringMachine {
+  pub fn new() -> Box<TuringMachine> {
+    let mut turing_machine = Box::new(TuringMachine {
+      clock_input: AudioInput::default(),
+      reset_input: AudioInput::default(),
+      cv_output: AudioOutput::default(),
+      gate_outputs: Default::default(),
+
+      length_param: AudioParam::new(AudioParamModulationType::Additive),
+      change_param: AudioParam::new(AudioParamModulationType::Additive),
+      scale_param: AudioParam::new(AudioParamModulationType::Additive),
+      seed_param: AudioParam::new(AudioParamModulationType::Additive),
+
+      clock_edge_detector: EdgeDetector::default(),
+      reset_edge_detector: EdgeDetector::default(),
+
+      register: 0,
+      rng: Rng::default(),
+      seed: 0,
+    });
+    turing_machine.restart();
+    turing_machine
+  }
+
+  fn restart(&mut self) {
+    self.rng = if self.seed == 0 {
+      Rng::unique()
+    } else {
+      Rng::new(self.seed)
+    };
+    self.register = self.rng.get_u32() as u16;
+  }
+}
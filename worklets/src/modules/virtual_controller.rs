@@ -2,11 +2,15 @@ use crate::audio_output::AudioOutput;
 use crate::audio_param::AudioParam;
 use crate::{
   modulate_core::QUANTUM_SIZE,
-  module::{Module, ModuleEvent},
+  module::Module,
+  tuning::{Tuning, REFERENCE_NOTE},
 };
 
 const NUM_PADS: usize = 4;
 
+// The keys of the keyboard are numbered from the C below the reference note.
+const FIRST_KEY_NOTE: i32 = REFERENCE_NOTE - 9;
+
 pub struct VirtualController {
   pressed_keys: [(f32, f32); 2],
   pads: [f32; NUM_PADS],
@@ -31,22 +35,39 @@ pub struct VirtualController {
   knob_c_output: AudioOutput,
   knob_d_output: AudioOutput,
 
-  events: Vec<ModuleEvent>,
+  // Voltages of the keys last pressed on each of the keyboards. Keys left unmapped by the tuning
+  // keep the previous voltage and don't open the gate.
+  keyboard_voltages: [f32; 2],
+  keyboard_gates: [f32; 2],
+
+  tuning: *const Tuning,
 }
 
 impl Module for VirtualController {
   fn process(&mut self, _quantum: u64) {
+    let tuning = unsafe { &*self.tuning };
+
+    for (keyboard, (key, gate)) in self.pressed_keys.iter().enumerate() {
+      match tuning.note_to_voltage(FIRST_KEY_NOTE + *key as i32) {
+        Some(voltage) => {
+          self.keyboard_voltages[keyboard] = voltage;
+          self.keyboard_gates[keyboard] = *gate;
+        }
+        None => self.keyboard_gates[keyboard] = 0.0,
+      }
+    }
+
     for sample in 0..QUANTUM_SIZE {
       self.pad_a_output[sample] = self.pads[0];
       self.pad_b_output[sample] = self.pads[1];
       self.pad_c_output[sample] = self.pads[2];
       self.pad_d_output[sample] = self.pads[3];
 
-      self.keyboard_first_cv_output[sample] = (self.pressed_keys[0].0 as f32 - 9.0) / 12.0;
-      self.keyboard_first_gate_output[sample] = self.pressed_keys[0].1;
+      self.keyboard_first_cv_output[sample] = self.keyboard_voltages[0];
+      self.keyboard_first_gate_output[sample] = self.keyboard_gates[0];
 
-      self.keyboard_second_cv_output[sample] = (self.pressed_keys[1].0 as f32 - 9.0) / 12.0;
-      self.keyboard_second_gate_output[sample] = self.pressed_keys[1].1;
+      self.keyboard_second_cv_output[sample] = self.keyboard_voltages[1];
+      self.keyboard_second_gate_output[sample] = self.keyboard_gates[1];
 
       self.knob_a_output[sample] = self.knob_a_param.at(sample);
       self.knob_b_output[sample] = self.knob_b_param.at(sample);
@@ -87,14 +108,10 @@ impl Module for VirtualController {
       self.pads.as_ptr() as usize,
     ]
   }
-
-  fn pop_event(&mut self) -> Option<ModuleEvent> {
-    self.events.pop()
-  }
 }
 
 impl VirtualController {
-  pub fn new() -> Box<VirtualController> {
+  pub fn new(tuning: &Tuning) -> Box<VirtualController> {
     Box::new(VirtualController {
       pressed_keys: [(0.0, 0.0); 2],
       pads: [0.0; NUM_PADS],
@@ -114,7 +131,9 @@ impl VirtualController {
       knob_b_output: AudioOutput::default(),
       knob_c_output: AudioOutput::default(),
       knob_d_output: AudioOutput::default(),
-      events: vec![],
+      keyboard_voltages: [0.0; 2],
+      keyboard_gates: [0.0; 2],
+      tuning,
     })
   }
 }
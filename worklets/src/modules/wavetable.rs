@@ -0,0 +1,224 @@
+use rustfft::{num_complex::Complex, FftPlanner};
+
+use crate::audio_output::AudioOutput;
+use crate::audio_param::{AudioParam, AudioParamModulationType};
+use crate::modulate_core::{INV_SAMPLE_RATE, QUANTUM_SIZE};
+use crate::module::Module;
+use crate::tuning::voltage_to_freq;
+use crate::util::lerp;
+
+// Each level of the mipmap holds half the harmonics of the previous one. The tables are twice as
+// long as needed to represent their harmonics, which keeps the linear interpolation error low.
+const MIPMAP_LEVELS: usize = 10;
+const MAX_HARMONICS: usize = 512;
+const MIN_TABLE_SIZE: usize = 64;
+
+struct MipmapLevel {
+  size: usize,
+  harmonics: usize,
+  // All frames of the table, one after another.
+  samples: Vec<f32>,
+}
+
+impl MipmapLevel {
+  fn read(&self, frame: usize, phase: f32) -> f32 {
+    let offset = frame * self.size;
+    let position = phase * self.size as f32;
+    let index = position as usize & (self.size - 1);
+    let next_index = (index + 1) & (self.size - 1);
+
+    lerp(
+      self.samples[offset + index],
+      self.samples[offset + next_index],
+      position.fract(),
+    )
+  }
+}
+
+// Band limited versions of all frames of a wavetable. They are built by the engine before it
+// locks the modules, as the FFTs take too long to stall the workers with, and then swapped into
+// the module.
+pub struct WavetableMipmap {
+  levels: Vec<MipmapLevel>,
+  frame_count: usize,
+}
+
+impl WavetableMipmap {
+  // Resamples the frames to the table sizes of the mipmap, band limiting each level by discarding
+  // the harmonics above its limit. The DC offset of the frames is removed as well. `frame_size` is
+  // the amount of samples in each of the frames.
+  pub fn build(samples: &[f32], frame_size: usize, frame_count: usize) -> WavetableMipmap {
+    if frame_size == 0 || frame_count == 0 || samples.len() < frame_size * frame_count {
+      return WavetableMipmap {
+        levels: vec![],
+        frame_count: 0,
+      };
+    }
+
+    let mut levels: Vec<MipmapLevel> = (0..MIPMAP_LEVELS)
+      .map(|level| {
+        let harmonics = MAX_HARMONICS >> level;
+        let size = usize::max(harmonics * 4, MIN_TABLE_SIZE);
+        MipmapLevel {
+          size,
+          harmonics,
+          samples: vec![0.0; size * frame_count],
+        }
+      })
+      .collect();
+
+    let mut fft_planner = FftPlanner::new();
+    let forward = fft_planner.plan_fft_forward(frame_size);
+    let mut spectrum = vec![Complex { re: 0.0, im: 0.0 }; frame_size];
+    let mut level_buffer = vec![Complex { re: 0.0, im: 0.0 }; MAX_HARMONICS * 4];
+
+    // Harmonics at or above half the frame size can't be represented by the frames.
+    let available_harmonics = (frame_size - 1) / 2;
+    let scale = 1.0 / frame_size as f32;
+
+    for frame in 0..frame_count {
+      for (bin, value) in spectrum.iter_mut().enumerate() {
+        *value = Complex {
+          re: samples[frame * frame_size + bin],
+          im: 0.0,
+        };
+      }
+      forward.process(&mut spectrum);
+
+      for level in levels.iter_mut() {
+        let buffer = &mut level_buffer[..level.size];
+        buffer.fill(Complex { re: 0.0, im: 0.0 });
+
+        for harmonic in 1..=usize::min(level.harmonics, available_harmonics) {
+          buffer[harmonic] = spectrum[harmonic] * scale;
+          buffer[level.size - harmonic] = spectrum[frame_size - harmonic] * scale;
+        }
+
+        fft_planner.plan_fft_inverse(level.size).process(buffer);
+
+        for (index, value) in buffer.iter().enumerate() {
+          level.samples[frame * level.size + index] = value.re;
+        }
+      }
+    }
+
+    WavetableMipmap {
+      levels,
+      frame_count,
+    }
+  }
+}
+
+pub struct Wavetable {
+  output: AudioOutput,
+
+  cv_param: AudioParam,
+  fm_param: AudioParam,
+  fine_param: AudioParam,
+  position_param: AudioParam,
+  level: AudioParam,
+
+  phase: f32,
+  // Frame position of the last sample, read by the main thread through `get_pointers`.
+  position: f32,
+
+  mipmap: Option<Box<WavetableMipmap>>,
+}
+
+impl Module for Wavetable {
+  fn process(&mut self, _quantum: u64) {
+    let Some(mipmap) = self
+      .mipmap
+      .as_deref()
+      .filter(|mipmap| mipmap.frame_count > 0)
+    else {
+      self.output.write_buffer_mut().fill(0.0);
+      return;
+    };
+
+    let last_frame = mipmap.frame_count - 1;
+
+    for sample in 0..QUANTUM_SIZE {
+      let cv = self.cv_param.at(sample);
+      let fm = self.fm_param.at(sample);
+      let fine = self.fine_param.at(sample);
+      let level = self.level.at(sample);
+
+      let freq = voltage_to_freq(cv + fm + fine / 12.0);
+      let dt = f32::min(freq * INV_SAMPLE_RATE, 0.5);
+
+      // Use the most detailed level whose harmonics all stay below the Nyquist frequency.
+      let mut mipmap_level = 0;
+      while mipmap_level < MIPMAP_LEVELS - 1
+        && mipmap.levels[mipmap_level].harmonics as f32 * dt > 0.5
+      {
+        mipmap_level += 1;
+      }
+      let table = &mipmap.levels[mipmap_level];
+
+      let position = self.position_param.at(sample).clamp(0.0, 1.0) * last_frame as f32;
+      let frame = usize::min(position as usize, last_frame);
+      let next_frame = usize::min(frame + 1, last_frame);
+
+      let value = lerp(
+        table.read(frame, self.phase),
+        table.read(next_frame, self.phase),
+        position - frame as f32,
+      );
+
+      self.output[sample] = value * level;
+      self.position = position;
+
+      self.phase += dt;
+      if self.phase >= 1.0 {
+        self.phase -= 1.0;
+      }
+    }
+  }
+
+  fn get_parameters(&mut self) -> Vec<&mut AudioParam> {
+    vec![
+      &mut self.cv_param,
+      &mut self.fm_param,
+      &mut self.fine_param,
+      &mut self.position_param,
+      &mut self.level,
+    ]
+  }
+
+  fn get_outputs(&mut self) -> Vec<&mut AudioOutput> {
+    vec![&mut self.output]
+  }
+
+  fn get_pointers(&mut self) -> Vec<usize> {
+    vec![&self.position as *const f32 as usize]
+  }
+
+  // The previous mipmap is handed back, so that the engine frees it after unlocking the modules.
+  fn swap_wavetable(&mut self, mipmap: &mut Option<Box<WavetableMipmap>>) {
+    std::mem::swap(&mut self.mipmap, mipmap);
+  }
+
+  fn reset(&mut self) {
+    self.phase = 0.0;
+  }
+}
+
+impl Wavetable {
+  pub fn new() -> Box<Wavetable> {
+    Box::new(Wavetable {
+      output: AudioOutput::default(),
+
+      cv_param: AudioParam::new(AudioParamModulationType::Additive),
+      fm_param: AudioParam::new(AudioParamModulationType::Multiplicative),
+      fine_param: AudioParam::new(AudioParamModulationType::Additive),
+      position_param: AudioParam::new(AudioParamModulationType::Additive),
+      level: AudioParam::new(AudioParamModulationType::Additive),
+
+      phase: 0.0,
+      position: 0.0,
+
+      mipmap: None,
+    })
+  }
+}
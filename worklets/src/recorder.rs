@@ -0,0 +1,348 @@
+use crate::{
+  audio_buffer::AudioBuffer,
+  modulate_core::{QUANTUM_SIZE, SAMPLE_RATE},
+};
+
+const NUM_CHANNELS: usize = 2;
+
+// A take is written into chunks of this length. While recording, the main thread hands over empty
+// chunks and collects the full ones, so that the barrier leader never has to allocate.
+const CHUNK_SECONDS: usize = 10;
+const CHUNK_SAMPLES: usize = CHUNK_SECONDS * SAMPLE_RATE * NUM_CHANNELS;
+// Full chunks which can wait to be collected, including the one made from the pre-roll.
+const MAX_PENDING_CHUNKS: usize = 4;
+
+const MAX_PRE_ROLL_SECONDS: f32 = 30.0;
+// Longest take, including the pre-roll, so that it can still be encoded to a WAV file.
+const MAX_RECORDING_SECONDS: usize = 20 * 60;
+const MAX_RECORDING_FRAMES: u64 = (MAX_RECORDING_SECONDS * SAMPLE_RATE) as u64;
+
+#[derive(PartialEq)]
+enum RecorderState {
+  Idle,
+  Armed,
+  Recording,
+}
+
+// Chunks passed between the main thread and the recorder, which are allocated and freed while the
+// engine isn't locked.
+pub struct RecorderExchange {
+  pub spare: Option<Vec<f32>>,
+  pub filled: Vec<Vec<f32>>,
+  // Whether the take was cut short, because it got too long or the main thread didn't collect
+  // the chunks in time.
+  pub truncated: bool,
+}
+
+impl RecorderExchange {
+  pub fn new(spare: Option<Vec<f32>>) -> RecorderExchange {
+    RecorderExchange {
+      spare,
+      filled: Vec::with_capacity(MAX_PENDING_CHUNKS + 1),
+      truncated: false,
+    }
+  }
+}
+
+// Records the final output of the engine. While armed, the output is written into a ring buffer
+// which is prepended to the recording once it is started, so a take can include the moments right
+// before the start was pressed.
+pub struct Recorder {
+  state: RecorderState,
+
+  pre_roll: Vec<f32>,
+  pre_roll_position: usize,
+  pre_roll_filled: bool,
+
+  // Interleaved stereo samples of the current take. `chunk` is being written to, and is replaced
+  // with `spare_chunk` once it is full.
+  chunk: Vec<f32>,
+  spare_chunk: Option<Vec<f32>>,
+  filled_chunks: Vec<Vec<f32>>,
+  truncated: bool,
+
+  // Amount of frames in the current take, read by the main thread through the pointer returned
+  // from `get_context_pointers`.
+  recorded_frames: u64,
+}
+
+impl Default for Recorder {
+  fn default() -> Self {
+    Recorder::new()
+  }
+}
+
+impl Recorder {
+  pub fn new() -> Recorder {
+    Recorder {
+      state: RecorderState::Idle,
+
+      pre_roll: vec![],
+      pre_roll_position: 0,
+      pre_roll_filled: false,
+
+      chunk: vec![],
+      spare_chunk: None,
+      filled_chunks: Vec::with_capacity(MAX_PENDING_CHUNKS),
+      truncated: false,
+
+      recorded_frames: 0,
+    }
+  }
+
+  pub fn recorded_frames_ptr(&self) -> usize {
+    &self.recorded_frames as *const u64 as usize
+  }
+
+  pub fn allocate_chunk() -> Vec<f32> {
+    Vec::with_capacity(CHUNK_SAMPLES)
+  }
+
+  // The pre-roll for `arm`, which is allocated before the engine is locked.
+  pub fn allocate_pre_roll(pre_roll_seconds: f32) -> Vec<f32> {
+    let pre_roll_frames =
+      (pre_roll_seconds.clamp(0.0, MAX_PRE_ROLL_SECONDS) * SAMPLE_RATE as f32) as usize;
+
+    vec![0.0; pre_roll_frames * NUM_CHANNELS]
+  }
+
+  // Exchanges `pre_roll` with the previous one, which is freed after the engine has been
+  // unlocked. A take which is being recorded can't be armed for.
+  pub fn arm(&mut self, pre_roll: &mut Vec<f32>) -> Result<(), String> {
+    if self.state == RecorderState::Recording {
+      return Err("can't arm while recording".to_string());
+    }
+
+    std::mem::swap(&mut self.pre_roll, pre_roll);
+    self.pre_roll_position = 0;
+    self.pre_roll_filled = false;
+    self.state = RecorderState::Armed;
+
+    Ok(())
+  }
+
+  // Starts a new take into the spare chunk of `exchange`, and hands back what is left of the
+  // previous take to be freed after the engine has been unlocked.
+  pub fn start(&mut self, exchange: &mut RecorderExchange) {
+    exchange.filled.append(&mut self.filled_chunks);
+    exchange.filled.push(std::mem::replace(
+      &mut self.chunk,
+      exchange.spare.take().unwrap_or_default(),
+    ));
+
+    self.recorded_frames = 0;
+    if self.state == RecorderState::Armed {
+      // The pre-roll is turned into the first chunk of the take, oldest sample first.
+      let mut pre_roll = std::mem::take(&mut self.pre_roll);
+      if self.pre_roll_filled {
+        pre_roll.rotate_left(self.pre_roll_position);
+      } else {
+        pre_roll.truncate(self.pre_roll_position);
+      }
+
+      self.recorded_frames = (pre_roll.len() / NUM_CHANNELS) as u64;
+      self.filled_chunks.push(pre_roll);
+    }
+
+    self.truncated = false;
+    self.state = RecorderState::Recording;
+  }
+
+  pub fn stop(&mut self) {
+    self.state = RecorderState::Idle;
+  }
+
+  fn truncate(&mut self) {
+    self.truncated = true;
+    self.stop();
+  }
+
+  // Hands over a spare chunk while recording, and collects the full ones.
+  pub fn exchange(&mut self, exchange: &mut RecorderExchange) {
+    if self.spare_chunk.is_none() && self.state == RecorderState::Recording {
+      self.spare_chunk = exchange.spare.take();
+    }
+
+    exchange.filled.append(&mut self.filled_chunks);
+    exchange.truncated |= self.truncated;
+  }
+
+  // Stops the take and hands over the rest of its samples, so that they can be encoded after the
+  // engine has been unlocked.
+  pub fn finish(&mut self, exchange: &mut RecorderExchange) {
+    self.stop();
+    self.exchange(exchange);
+
+    exchange.filled.push(std::mem::take(&mut self.chunk));
+    exchange.spare = self.spare_chunk.take();
+    self.recorded_frames = 0;
+  }
+
+  pub fn process(&mut self, left: &AudioBuffer, right: &AudioBuffer) {
+    match self.state {
+      RecorderState::Idle => {}
+      RecorderState::Armed => {
+        if self.pre_roll.is_empty() {
+          return;
+        }
+
+        for sample in 0..QUANTUM_SIZE {
+          self.pre_roll[self.pre_roll_position] = left[sample];
+          self.pre_roll[self.pre_roll_position + 1] = right[sample];
+
+          self.pre_roll_position += NUM_CHANNELS;
+          if self.pre_roll_position >= self.pre_roll.len() {
+            self.pre_roll_position = 0;
+            self.pre_roll_filled = true;
+          }
+        }
+      }
+      RecorderState::Recording => {
+        if self.recorded_frames + QUANTUM_SIZE as u64 > MAX_RECORDING_FRAMES {
+          self.truncate();
+          return;
+        }
+
+        if self.chunk.len() + QUANTUM_SIZE * NUM_CHANNELS > self.chunk.capacity() {
+          if self.filled_chunks.len() >= MAX_PENDING_CHUNKS {
+            self.truncate();
+            return;
+          }
+
+          let Some(spare_chunk) = self.spare_chunk.take() else {
+            self.truncate();
+            return;
+          };
+
+          let chunk = std::mem::replace(&mut self.chunk, spare_chunk);
+          self.filled_chunks.push(chunk);
+        }
+
+        for sample in 0..QUANTUM_SIZE {
+          self.chunk.push(left[sample]);
+          self.chunk.push(right[sample]);
+        }
+
+        self.recorded_frames += QUANTUM_SIZE as u64;
+      }
+    }
+  }
+
+  // Encodes the chunks of a take as a 32-bit floating point WAV file.
+  pub fn encode_wav(chunks: &[Vec<f32>]) -> Vec<u8> {
+    const FORMAT_IEEE_FLOAT: u16 = 3;
+    const BYTES_PER_SAMPLE: usize = 4;
+
+    let samples: usize = chunks.iter().map(|chunk| chunk.len()).sum();
+    let data_size = samples * BYTES_PER_SAMPLE;
+    let frames = (samples / NUM_CHANNELS) as u32;
+    let block_align = (NUM_CHANNELS * BYTES_PER_SAMPLE) as u16;
+
+    let mut wav = Vec::with_capacity(58 + data_size);
+
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&((50 + data_size) as u32).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&18u32.to_le_bytes());
+    wav.extend_from_slice(&FORMAT_IEEE_FLOAT.to_le_bytes());
+    wav.extend_from_slice(&(NUM_CHANNELS as u16).to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE as u32).to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE as u32 * block_align as u32).to_le_bytes());
+    wav.extend_from_slice(&block_align.to_le_bytes());
+    wav.extend_from_slice(&(BYTES_PER_SAMPLE as u16 * 8).to_le_bytes());
+    wav.extend_from_slice(&0u16.to_le_bytes());
+
+    wav.extend_from_slice(b"fact");
+    wav.extend_from_slice(&4u32.to_le_bytes());
+    wav.extend_from_slice(&frames.to_le_bytes());
+
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(data_size as u32).to_le_bytes());
+    for sample in chunks.iter().flatten() {
+      wav.extend_from_slice(&sample.to_le_bytes());
+    }
+
+    wav
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn u16_at(wav: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(wav[offset..offset + 2].try_into().unwrap())
+  }
+
+  fn u32_at(wav: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(wav[offset..offset + 4].try_into().unwrap())
+  }
+
+  fn f32_at(wav: &[u8], offset: usize) -> f32 {
+    f32::from_le_bytes(wav[offset..offset + 4].try_into().unwrap())
+  }
+
+  #[test]
+  fn encodes_wav_header() {
+    let wav = Recorder::encode_wav(&[vec![0.5, -0.5, 0.25], vec![-0.25]]);
+
+    assert_eq!(wav.len(), 58 + 16);
+    assert_eq!(&wav[0..4], b"RIFF");
+    assert_eq!(u32_at(&wav, 4), 50 + 16);
+    assert_eq!(&wav[8..12], b"WAVE");
+
+    assert_eq!(&wav[12..16], b"fmt ");
+    assert_eq!(u32_at(&wav, 16), 18);
+    assert_eq!(u16_at(&wav, 20), 3);
+    assert_eq!(u16_at(&wav, 22), NUM_CHANNELS as u16);
+    assert_eq!(u32_at(&wav, 24), SAMPLE_RATE as u32);
+    assert_eq!(u32_at(&wav, 28), SAMPLE_RATE as u32 * 8);
+    assert_eq!(u16_at(&wav, 32), 8);
+    assert_eq!(u16_at(&wav, 34), 32);
+
+    assert_eq!(&wav[38..42], b"fact");
+    assert_eq!(u32_at(&wav, 46), 2);
+
+    assert_eq!(&wav[50..54], b"data");
+    assert_eq!(u32_at(&wav, 54), 16);
+    // The chunks are joined in order.
+    assert_eq!(f32_at(&wav, 58), 0.5);
+    assert_eq!(f32_at(&wav, 66), 0.25);
+    assert_eq!(f32_at(&wav, 70), -0.25);
+  }
+
+  #[test]
+  fn starts_with_the_pre_roll_in_order() {
+    let mut recorder = Recorder::new();
+    let mut pre_roll = vec![0.0; 3 * QUANTUM_SIZE * NUM_CHANNELS];
+    recorder.arm(&mut pre_roll).unwrap();
+
+    // Five quanta into a pre-roll of three, so the ring wraps around.
+    for quantum in 0..5 {
+      let buffer = AudioBuffer([quantum as f32; QUANTUM_SIZE]);
+      recorder.process(&buffer, &buffer);
+    }
+
+    let mut exchange = RecorderExchange::new(Some(Recorder::allocate_chunk()));
+    recorder.start(&mut exchange);
+    let mut exchange = RecorderExchange::new(None);
+    recorder.finish(&mut exchange);
+
+    let samples: Vec<f32> = exchange.filled.iter().flatten().copied().collect();
+    assert_eq!(samples.len(), 3 * QUANTUM_SIZE * NUM_CHANNELS);
+    assert_eq!(samples[0], 2.0);
+    assert_eq!(samples[samples.len() - 1], 4.0);
+    assert!(!exchange.truncated);
+  }
+
+  #[test]
+  fn cannot_arm_while_recording() {
+    let mut recorder = Recorder::new();
+    let mut exchange = RecorderExchange::new(Some(Recorder::allocate_chunk()));
+    recorder.start(&mut exchange);
+
+    assert!(recorder.arm(&mut vec![]).is_err());
+  }
+}
@@ -22,4 +22,8 @@ impl RingBuffer {
   pub fn read(&self) -> f32 {
     self.buffer[self.position]
   }
+
+  pub fn reset(&mut self) {
+    self.buffer.fill(0.0);
+  }
 }
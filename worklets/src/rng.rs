@@ -0,0 +1,51 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const MULTIPLIER: u64 = 6364136223846793005;
+const INCREMENT: u64 = 1;
+
+// Handed out by `Rng::unique`, so that every generator created with it gets its own sequence.
+static NEXT_SEED: AtomicU64 = AtomicU64::new(1);
+
+// PCG32 random number generator.
+#[derive(Default)]
+pub struct Rng {
+  state: u64,
+}
+
+impl Rng {
+  pub fn new(seed: u64) -> Rng {
+    let mut rng = Rng { state: 0 };
+    rng.get_u32();
+    rng.state = rng.state.wrapping_add(seed);
+    rng.get_u32();
+    rng
+  }
+
+  // A generator whose sequence is decorrelated from all the others created with this function,
+  // for modules that would otherwise output the exact same noise when there's several of them.
+  pub fn unique() -> Rng {
+    let seed = NEXT_SEED.fetch_add(1, Ordering::Relaxed);
+    // Spread the consecutive seeds far apart in the sequence of the generator.
+    Rng::new(seed.wrapping_mul(0x9e3779b97f4a7c15))
+  }
+
+  pub fn get_u32(&mut self) -> u32 {
+    let oldstate = self.state;
+    // Advance internal state
+    self.state = oldstate.wrapping_mul(MULTIPLIER).wrapping_add(INCREMENT);
+    // Calculate output function (XSH RR), uses old state for max ILP
+    let xorshifted: u32 = (((oldstate >> 18u64) ^ oldstate) >> 27u64) as u32;
+    let rot: u32 = (oldstate >> 59u64) as u32;
+    return (xorshifted >> rot) | (xorshifted << ((0u32.wrapping_sub(rot)) & 31));
+  }
+
+  // Uniformly distributed in [0, 1).
+  pub fn get_f32(&mut self) -> f32 {
+    self.get_u32() as f32 * 2.3283064e-10
+  }
+
+  // Uniformly distributed in [-1, 1).
+  pub fn get_bipolar_f32(&mut self) -> f32 {
+    self.get_f32() * 2.0 - 1.0
+  }
+}
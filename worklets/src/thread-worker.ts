@@ -6,8 +6,17 @@ self.onmessage = async (
   try {
     initSync({ module: event.data[0], memory: event.data[1] })
     self.postMessage(null)
-    workerEntry(event.data[2])
   } catch (err) {
     self.postMessage(err)
+    return
+  }
+
+  // The worker never returns. Panics are handled by the engine before they
+  // abort the call, so getting here means the WebAssembly instance trapped
+  // for some other reason, and the worker can't be resumed.
+  try {
+    workerEntry(event.data[2])
+  } catch (err) {
+    console.error('worker stopped:', err)
   }
 }
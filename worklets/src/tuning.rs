@@ -0,0 +1,453 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+// Pitch is 1V/oct, with 0V at the reference frequency, which is the one of A4 (440 Hz) by default.
+// The frequency is shared by the whole engine, and stored as the bits of an `f32`. It only applies
+// to pitch, so rates and cutoffs such as the ones of the LFO and the filters don't follow it.
+static REFERENCE_FREQUENCY: AtomicU32 = AtomicU32::new(0x43dc0000);
+
+pub fn reference_frequency() -> f32 {
+  f32::from_bits(REFERENCE_FREQUENCY.load(Ordering::Relaxed))
+}
+
+// Range of reference frequencies in Hz which keep the voltages of audible notes within reach.
+const MIN_REFERENCE_FREQUENCY: f32 = 1.0;
+const MAX_REFERENCE_FREQUENCY: f32 = 2000.0;
+
+pub fn set_reference_frequency(freq: f32) -> Result<(), String> {
+  // Also rejects NaN.
+  if !(MIN_REFERENCE_FREQUENCY..=MAX_REFERENCE_FREQUENCY).contains(&freq) {
+    return Err(format!(
+      "reference frequency {} is outside of {}-{} Hz",
+      freq, MIN_REFERENCE_FREQUENCY, MAX_REFERENCE_FREQUENCY
+    ));
+  }
+
+  REFERENCE_FREQUENCY.store(freq.to_bits(), Ordering::Relaxed);
+  Ok(())
+}
+
+pub fn voltage_to_freq(voltage: f32) -> f32 {
+  reference_frequency() * f32::powf(2.0, voltage)
+}
+
+// Notes are numbered like MIDI notes, and the reference note is the one playing at 0V.
+pub const NUM_NOTES: usize = 128;
+pub const REFERENCE_NOTE: i32 = 57;
+
+// Scala numbers notes as MIDI does, with A4 at 69 rather than at the reference note of the engine.
+const SCALA_NOTE_OFFSET: i32 = 69 - REFERENCE_NOTE;
+// The mapping Scala uses without a `.kbm` file, with the first degree of the scale on middle C and
+// A4 at the reference frequency.
+const SCALA_MIDDLE_NOTE: i32 = 60;
+const SCALA_REFERENCE_NOTE: i32 = 69;
+
+// A scale of a Scala `.scl` file. The pitches are in cents above the first degree of the scale,
+// which isn't listed, and the last pitch is the period the scale repeats at, usually an octave.
+pub struct Scale {
+  pub description: String,
+  pub pitches: Vec<f64>,
+}
+
+impl Scale {
+  // Cents of a degree of the scale, counting in both directions from the first degree.
+  fn cents(&self, degree: i32) -> f64 {
+    let size = self.pitches.len() as i32;
+    let period = self.pitches[self.pitches.len() - 1];
+    let octave = degree.div_euclid(size);
+    let step = degree.rem_euclid(size);
+
+    let cents = if step == 0 {
+      0.0
+    } else {
+      self.pitches[step as usize - 1]
+    };
+
+    octave as f64 * period + cents
+  }
+}
+
+// Lines of Scala files starting with an exclamation mark are comments.
+fn data_lines(source: &str) -> impl Iterator<Item = (usize, &str)> {
+  source
+    .lines()
+    .enumerate()
+    .filter(|(_, line)| !line.starts_with('!'))
+    .map(|(index, line)| (index + 1, line.trim()))
+}
+
+fn parse_pitch(line_number: usize, line: &str) -> Result<f64, String> {
+  let value = line.split_whitespace().next().unwrap_or("");
+
+  let cents = if value.contains('.') {
+    value.parse::<f64>().ok()
+  } else {
+    let (numerator, denominator) = value.split_once('/').unwrap_or((value, "1"));
+    match (numerator.parse::<u64>(), denominator.parse::<u64>()) {
+      (Ok(numerator), Ok(denominator)) if numerator > 0 && denominator > 0 => {
+        Some(1200.0 * f64::log2(numerator as f64 / denominator as f64))
+      }
+      _ => None,
+    }
+  };
+
+  cents.ok_or_else(|| format!("line {}: invalid pitch \"{}\"", line_number, value))
+}
+
+pub fn parse_scl(source: &str) -> Result<Scale, String> {
+  let mut lines = data_lines(source);
+
+  let description = lines
+    .next()
+    .map(|(_, line)| line.to_string())
+    .ok_or("missing description")?;
+
+  let (line_number, count) = lines.next().ok_or("missing number of notes")?;
+  let count = count
+    .split_whitespace()
+    .next()
+    .and_then(|count| count.parse::<usize>().ok())
+    .ok_or_else(|| format!("line {}: invalid number of notes", line_number))?;
+
+  if count == 0 {
+    return Err("the scale has no notes".to_string());
+  }
+
+  let pitches = lines
+    .take(count)
+    .map(|(line_number, line)| parse_pitch(line_number, line))
+    .collect::<Result<Vec<f64>, String>>()?;
+
+  if pitches.len() != count {
+    return Err(format!("expected {} notes, found {}", count, pitches.len()));
+  }
+
+  Ok(Scale {
+    description,
+    pitches,
+  })
+}
+
+// A keyboard mapping of a Scala `.kbm` file, which assigns the degrees of a scale to notes.
+pub struct KeyboardMapping {
+  // Amount of notes the mapping repeats after, or 0 for a linear mapping of one degree per note.
+  pub size: usize,
+  pub first_note: i32,
+  pub last_note: i32,
+  // The note the first degree of the scale is mapped to.
+  pub middle_note: i32,
+  pub reference_note: i32,
+  pub reference_frequency: f64,
+  // The degree of the scale the mapping repeats at.
+  pub octave_degree: i32,
+  // Degrees of the scale for each note of the mapping, `None` for the ones left unmapped.
+  pub degrees: Vec<Option<i32>>,
+}
+
+fn next_value<'a>(
+  lines: &mut impl Iterator<Item = (usize, &'a str)>,
+  name: &str,
+) -> Result<(usize, &'a str), String> {
+  let (line_number, line) = lines.next().ok_or_else(|| format!("missing {}", name))?;
+  Ok((line_number, line.split_whitespace().next().unwrap_or("")))
+}
+
+fn next_integer<'a>(
+  lines: &mut impl Iterator<Item = (usize, &'a str)>,
+  name: &str,
+) -> Result<i32, String> {
+  let (line_number, value) = next_value(lines, name)?;
+  value
+    .parse::<i32>()
+    .map_err(|_| format!("line {}: invalid {}", line_number, name))
+}
+
+pub fn parse_kbm(source: &str) -> Result<KeyboardMapping, String> {
+  let mut lines = data_lines(source).filter(|(_, line)| !line.is_empty());
+
+  let size = next_integer(&mut lines, "map size")?.max(0) as usize;
+  let first_note = next_integer(&mut lines, "first note")?;
+  let last_note = next_integer(&mut lines, "last note")?;
+  let middle_note = next_integer(&mut lines, "middle note")?;
+  let reference_note = next_integer(&mut lines, "reference note")?;
+
+  let (line_number, value) = next_value(&mut lines, "reference frequency")?;
+  let reference_frequency = value
+    .parse::<f64>()
+    .ok()
+    .filter(|freq| *freq > 0.0)
+    .ok_or_else(|| format!("line {}: invalid reference frequency", line_number))?;
+
+  let octave_degree = next_integer(&mut lines, "octave degree")?;
+
+  // Entries missing from the end of the mapping are left unmapped.
+  let mut degrees = vec![None; size];
+  for degree in degrees.iter_mut() {
+    let Ok((line_number, value)) = next_value(&mut lines, "degree") else {
+      break;
+    };
+
+    *degree = match value {
+      "x" | "X" => None,
+      value => Some(
+        value
+          .parse::<i32>()
+          .map_err(|_| format!("line {}: invalid degree", line_number))?,
+      ),
+    };
+  }
+
+  Ok(KeyboardMapping {
+    size,
+    first_note,
+    last_note,
+    middle_note,
+    reference_note,
+    reference_frequency,
+    octave_degree,
+    degrees,
+  })
+}
+
+// Maps notes to voltages, in 12 tone equal temperament unless a Scala scale has been loaded.
+pub struct Tuning {
+  scale: Option<Scale>,
+  mapping: Option<KeyboardMapping>,
+
+  // `None` for the notes left unmapped by the keyboard mapping.
+  voltages: [Option<f32>; NUM_NOTES],
+}
+
+impl Default for Tuning {
+  fn default() -> Self {
+    let mut tuning = Tuning {
+      scale: None,
+      mapping: None,
+      voltages: [None; NUM_NOTES],
+    };
+    tuning.update();
+    tuning
+  }
+}
+
+impl Tuning {
+  pub fn set_scale(&mut self, scale: Option<Scale>, mapping: Option<KeyboardMapping>) {
+    self.scale = scale;
+    self.mapping = mapping;
+    self.update();
+  }
+
+  pub fn note_to_voltage(&self, note: i32) -> Option<f32> {
+    if note < 0 || note >= NUM_NOTES as i32 {
+      return None;
+    }
+
+    self.voltages[note as usize]
+  }
+
+  // The mapped note closest to the voltage out of the enabled ones, with its voltage.
+  pub fn nearest_note(&self, voltage: f32, is_enabled: impl Fn(i32) -> bool) -> Option<(i32, f32)> {
+    let mut nearest: Option<(i32, f32)> = None;
+
+    for (note, note_voltage) in self.voltages.iter().enumerate() {
+      let Some(note_voltage) = *note_voltage else {
+        continue;
+      };
+
+      let is_nearer = match nearest {
+        Some((_, nearest_voltage)) => {
+          (note_voltage - voltage).abs() < (nearest_voltage - voltage).abs()
+        }
+        None => true,
+      };
+
+      if is_nearer && is_enabled(note as i32) {
+        nearest = Some((note as i32, note_voltage));
+      }
+    }
+
+    nearest
+  }
+
+  // Rebuilds the voltages of the notes. Needed when the scale or the reference frequency changes,
+  // as keyboard mappings tune their reference note to an absolute frequency.
+  pub fn update(&mut self) {
+    for note in 0..NUM_NOTES {
+      self.voltages[note] = self.compute_voltage(note as i32);
+    }
+  }
+
+  fn compute_voltage(&self, note: i32) -> Option<f32> {
+    let Some(scale) = &self.scale else {
+      return Some((note - REFERENCE_NOTE) as f32 / 12.0);
+    };
+
+    let scala_note = note + SCALA_NOTE_OFFSET;
+
+    match &self.mapping {
+      Some(mapping) => {
+        if scala_note < mapping.first_note || scala_note > mapping.last_note {
+          return None;
+        }
+
+        let degree = Tuning::mapped_degree(mapping, scala_note)?;
+        // The reference note is tuned to the reference frequency even when it's left unmapped.
+        let reference_degree = Tuning::mapped_degree(mapping, mapping.reference_note)
+          .unwrap_or(mapping.reference_note - mapping.middle_note);
+
+        let cents = scale.cents(degree) - scale.cents(reference_degree);
+        let ratio = mapping.reference_frequency / reference_frequency() as f64;
+
+        Some((f64::log2(ratio) + cents / 1200.0) as f32)
+      }
+      None => {
+        let cents = scale.cents(scala_note - SCALA_MIDDLE_NOTE)
+          - scale.cents(SCALA_REFERENCE_NOTE - SCALA_MIDDLE_NOTE);
+
+        Some((cents / 1200.0) as f32)
+      }
+    }
+  }
+
+  fn mapped_degree(mapping: &KeyboardMapping, note: i32) -> Option<i32> {
+    let offset = note - mapping.middle_note;
+
+    if mapping.size == 0 {
+      return Some(offset);
+    }
+
+    let size = mapping.size as i32;
+    let repetition = offset.div_euclid(size);
+    let degree = mapping.degrees[offset.rem_euclid(size) as usize]?;
+
+    Some(repetition * mapping.octave_degree + degree)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const EQUAL_TEMPERAMENT_SCL: &str = "! 12tet.scl
+!
+12 tone equal temperament
+ 12
+!
+ 100.0
+ 200.0
+ 300.0
+ 400.0
+ 500.0
+ 600.0
+ 700.0
+ 800.0
+ 900.0
+ 1000.0
+ 1100.0
+ 2/1
+";
+
+  const STANDARD_KBM: &str = "! standard.kbm
+! Map size
+12
+! First and last note
+0
+127
+! Middle note, reference note and frequency
+60
+69
+440.0
+! Octave degree
+12
+! Mapping
+0
+1
+2
+3
+4
+5
+6
+7
+8
+9
+10
+11
+";
+
+  fn assert_same_voltages(tuning: &Tuning, expected: &Tuning, notes: std::ops::Range<i32>) {
+    for note in notes {
+      let voltage = tuning.note_to_voltage(note).unwrap();
+      let expected_voltage = expected.note_to_voltage(note).unwrap();
+      assert!(
+        (voltage - expected_voltage).abs() < 1e-5,
+        "note {}: {} != {}",
+        note,
+        voltage,
+        expected_voltage
+      );
+    }
+  }
+
+  #[test]
+  fn parses_ratios_and_cents() {
+    let scale = parse_scl("Just fifth\n2\n3/2\n1200.0\n").unwrap();
+
+    assert_eq!(scale.description, "Just fifth");
+    assert!((scale.pitches[0] - 701.955).abs() < 1e-3);
+    assert_eq!(scale.pitches[1], 1200.0);
+  }
+
+  #[test]
+  fn rejects_invalid_scales() {
+    assert!(parse_scl("").is_err());
+    assert!(parse_scl("Empty\n0\n").is_err());
+    assert!(parse_scl("Short\n3\n100.0\n200.0\n").is_err());
+    assert!(parse_scl("Zero\n1\n0/1\n").is_err());
+  }
+
+  #[test]
+  fn parses_keyboard_mapping() {
+    let mapping = parse_kbm(STANDARD_KBM).unwrap();
+
+    assert_eq!(mapping.size, 12);
+    assert_eq!(mapping.middle_note, 60);
+    assert_eq!(mapping.reference_note, 69);
+    assert_eq!(mapping.reference_frequency, 440.0);
+    assert_eq!(mapping.degrees[11], Some(11));
+  }
+
+  #[test]
+  fn equal_temperament_scale_matches_default() {
+    let mut tuning = Tuning::default();
+    tuning.set_scale(Some(parse_scl(EQUAL_TEMPERAMENT_SCL).unwrap()), None);
+
+    assert_same_voltages(&tuning, &Tuning::default(), 0..NUM_NOTES as i32);
+  }
+
+  #[test]
+  fn equal_temperament_mapping_matches_default() {
+    let mut tuning = Tuning::default();
+    tuning.set_scale(
+      Some(parse_scl(EQUAL_TEMPERAMENT_SCL).unwrap()),
+      Some(parse_kbm(STANDARD_KBM).unwrap()),
+    );
+
+    // The mapping ends at the last MIDI note, which is below the last note of the engine.
+    let last_mapped_note = 127 - SCALA_NOTE_OFFSET;
+    assert_same_voltages(&tuning, &Tuning::default(), 0..last_mapped_note + 1);
+    assert_eq!(tuning.note_to_voltage(last_mapped_note + 1), None);
+  }
+
+  #[test]
+  fn unmapped_notes_have_no_voltage() {
+    let kbm = STANDARD_KBM.replacen("\n0\n1\n2\n", "\nx\n1\n2\n", 1);
+    let mut tuning = Tuning::default();
+    tuning.set_scale(
+      Some(parse_scl(EQUAL_TEMPERAMENT_SCL).unwrap()),
+      Some(parse_kbm(&kbm).unwrap()),
+    );
+
+    // C, the first degree of the mapping, is left unmapped.
+    assert_eq!(tuning.note_to_voltage(REFERENCE_NOTE - 9), None);
+    assert_eq!(tuning.note_to_voltage(REFERENCE_NOTE), Some(0.0));
+  }
+}
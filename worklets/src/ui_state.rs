@@ -0,0 +1,115 @@
+use std::sync::atomic::{fence, AtomicU32, Ordering};
+
+// State of a module which the UI reads straight from memory, through a pointer from the module's
+// `get_pointers`, instead of having it sent as events. The data is written once per quantum
+// between two increments of a sequence counter, which is odd while a write is in progress, so
+// the UI can read it without locking and retry when the counter changed under it.
+//
+// The UI reads the counter as a u32 followed by the data as f32s, so the data should only consist
+// of f32s and be `#[repr(C)]` when it's a struct.
+#[repr(C)]
+pub struct UiState<T: Copy> {
+  sequence: AtomicU32,
+  data: T,
+}
+
+impl<T: Copy> UiState<T> {
+  pub fn new(data: T) -> UiState<T> {
+    UiState {
+      sequence: AtomicU32::new(0),
+      data,
+    }
+  }
+
+  pub fn write(&mut self, data: T) {
+    let sequence = self.sequence.load(Ordering::Relaxed);
+    self
+      .sequence
+      .store(sequence.wrapping_add(1), Ordering::Relaxed);
+    fence(Ordering::Release);
+
+    // Volatile, as the compiler can't see the reads of the main thread.
+    unsafe {
+      std::ptr::write_volatile(&mut self.data, data);
+    }
+
+    self
+      .sequence
+      .store(sequence.wrapping_add(2), Ordering::Release);
+  }
+
+  pub fn as_ptr(&self) -> usize {
+    self as *const UiState<T> as usize
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[repr(C)]
+  #[derive(Clone, Copy)]
+  struct State {
+    position: f32,
+    value: f32,
+  }
+
+  // Reads the state like the UI does, as a u32 counter followed by f32s.
+  fn read(ui_state: &UiState<State>) -> (u32, [f32; 2]) {
+    let ptr = ui_state.as_ptr();
+    unsafe {
+      let sequence = *(ptr as *const u32);
+      let data = *((ptr + 4) as *const [f32; 2]);
+      (sequence, data)
+    }
+  }
+
+  #[test]
+  fn writes_data_after_the_counter() {
+    let mut ui_state = UiState::new(State {
+      position: 0.0,
+      value: 0.0,
+    });
+    assert_eq!(read(&ui_state), (0, [0.0, 0.0]));
+
+    ui_state.write(State {
+      position: 1.0,
+      value: 0.5,
+    });
+    assert_eq!(read(&ui_state), (2, [1.0, 0.5]));
+  }
+
+  #[test]
+  fn counter_is_even_between_writes() {
+    let mut ui_state = UiState::new(State {
+      position: 0.0,
+      value: 0.0,
+    });
+
+    for write in 1..=10u32 {
+      ui_state.write(State {
+        position: write as f32,
+        value: 0.0,
+      });
+
+      let (sequence, data) = read(&ui_state);
+      assert_eq!(sequence, write * 2);
+      assert_eq!(data[0], write as f32);
+    }
+  }
+
+  #[test]
+  fn counter_wraps_around() {
+    let mut ui_state = UiState::new(State {
+      position: 0.0,
+      value: 0.0,
+    });
+    ui_state.sequence.store(u32::MAX - 1, Ordering::Relaxed);
+
+    ui_state.write(State {
+      position: 1.0,
+      value: 0.0,
+    });
+    assert_eq!(read(&ui_state).0, 0);
+  }
+}
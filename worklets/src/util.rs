@@ -6,6 +6,11 @@ pub fn exp_curve(x: f32) -> f32 {
   (3.0 + x * (-13.0 + 5.0 * x)) / (3.0 + 2.0 * x)
 }
 
+// Derivative of `exp_curve`.
+pub fn exp_curve_slope(x: f32) -> f32 {
+  (-45.0 + x * (30.0 + 10.0 * x)) / ((3.0 + 2.0 * x) * (3.0 + 2.0 * x))
+}
+
 pub fn tension_interp(start: f32, end: f32, tension: f32, t: f32) -> f32 {
   start
     + (end - start)
@@ -17,3 +22,30 @@ pub fn tension_interp(start: f32, end: f32, tension: f32, t: f32) -> f32 {
         f32::powf(1.0 - f32::powf(1.0 - t.clamp(0.0, 1.0), exp), 1.0 / exp)
       }
 }
+
+// Residual of a band-limited unit step, approximated with a polynomial. `t` is the phase in [0, 1)
+// relative to the discontinuity and `dt` the phase increment per sample.
+pub fn poly_blep(t: f32, dt: f32) -> f32 {
+  if t < dt {
+    let x = t / dt;
+    -0.5 * (1.0 - x) * (1.0 - x)
+  } else if t > 1.0 - dt {
+    let x = (t - 1.0) / dt;
+    0.5 * (x + 1.0) * (x + 1.0)
+  } else {
+    0.0
+  }
+}
+
+// Residual of a band-limited unit change of slope per sample, the integral of `poly_blep`.
+pub fn poly_blamp(t: f32, dt: f32) -> f32 {
+  if t < dt {
+    let x = 1.0 - t / dt;
+    x * x * x / 6.0
+  } else if t > 1.0 - dt {
+    let x = (t - 1.0) / dt + 1.0;
+    x * x * x / 6.0
+  } else {
+    0.0
+  }
+}
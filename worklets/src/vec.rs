@@ -1,6 +1,7 @@
 use serde::Serialize;
 
 #[derive(Copy, Clone, Default, Serialize)]
+#[repr(C)]
 pub struct Vec2 {
   pub x: f32,
   pub y: f32,